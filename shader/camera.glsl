@@ -0,0 +1,13 @@
+// added to every shader right after the version line, filled from the camera of the view being drawn
+layout(std140) uniform Camera {
+    // world to viewport pixels
+    mat4 uView;
+    // viewport pixels to clip space
+    mat4 uProjection;
+    // viewport pixels per world unit
+    float uZoom;
+};
+
+vec4 worldToClip(vec2 world) {
+    return uProjection * uView * vec4(world, 0., 1.);
+}
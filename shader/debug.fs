@@ -0,0 +1,27 @@
+#version 330
+#define THICKNESS 1.5
+
+flat in vec2 vStart;
+flat in vec2 vEnd;
+flat in float vRadius;
+flat in vec3 vColor;
+in vec2 vLocal;
+
+void main() {
+    // only the outline, inside distance to the closest edge
+    vec2 p = vLocal;
+    vec2 ab = vEnd - vStart;
+    float edge;
+    if(vRadius < 0.) {
+        edge = min(min(p.x, ab.x - p.x), min(p.y, ab.y - p.y));
+    } else {
+        float t = dot(ab, ab) == 0. ? 0. : clamp(dot(p, ab) / dot(ab, ab), 0., 1.);
+        edge = vRadius - length(p - ab * t);
+    }
+    // as thick on screen whatever the zoom
+    edge *= uZoom;
+    if(edge < 0. || edge > THICKNESS) {
+        discard;
+    }
+    gl_FragColor = vec4(vColor, 1.);
+}
@@ -0,0 +1,26 @@
+#version 330
+layout (location = 0) in vec3 aPosition;
+// per shape, in world units
+// a box goes from start to end & has a negative radius, otherwise it is a capsule, or a circle when both ends match
+layout (location = 1) in vec2 aStart;
+layout (location = 2) in vec2 aEnd;
+layout (location = 3) in float aRadius;
+layout (location = 4) in vec3 aColor;
+
+flat out vec2 vStart;
+flat out vec2 vEnd;
+flat out float vRadius;
+flat out vec3 vColor;
+// from the start, in world units
+out vec2 vLocal;
+
+void main() {
+    vStart = aStart;
+    vEnd = aEnd;
+    vRadius = aRadius;
+    vColor = aColor;
+    float pad = max(aRadius, 0.);
+    vec2 pos = mix(min(aStart, aEnd) - pad, max(aStart, aEnd) + pad, aPosition.xy * 0.5 + 0.5);
+    vLocal = pos - aStart;
+    gl_Position = worldToClip(pos);
+}
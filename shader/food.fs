@@ -1,17 +1,16 @@
 #version 330
 
-uniform vec2 uPosition;
-uniform float uRadius;
-uniform float uTime;
+in vec2 vLocal;
+flat in float vRadius;
+flat in float vPhase;
+flat in uint vKind;
 
 void main(){  
-    if(length(gl_FragCoord.xy - uPosition) < uRadius - (0.5 * uTime)){
-        gl_FragColor=vec4(
-         1. * max(uTime,(1-(length(gl_FragCoord.xy - uPosition) / uRadius))),
-         1.,
-         1. * max(uTime,(1-(length(gl_FragCoord.xy - uPosition) / uRadius))),
-         1.);
+    float dst = length(vLocal);
+    if(dst < vRadius - (0.5 * vPhase)){
+        float fac = max(vPhase,(1-(dst / vRadius)));
+        gl_FragColor=vec4(mix(uFood[vKind].rgb, uFoodHighlight.rgb, fac), 1.);
     }else {
         discard;
     };
-}
\ No newline at end of file
+}
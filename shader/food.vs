@@ -0,0 +1,25 @@
+#version 330
+layout (location = 0) in vec3 aPosition;
+// per food, in world units
+layout (location = 1) in vec2 aCenter;
+layout (location = 2) in float aRadius;
+// -1 to 1, lightens & shrinks it
+layout (location = 3) in float aPhase;
+// index in uFood
+layout (location = 4) in float aKind;
+
+// from the center, in world units
+out vec2 vLocal;
+flat out float vRadius;
+flat out float vPhase;
+flat out uint vKind;
+
+void main() {
+    vRadius = aRadius;
+    vPhase = aPhase;
+    vKind = uint(aKind);
+    // a negative phase grows it past its radius
+    vLocal = aPosition.xy * (aRadius + 1.);
+    vec2 pos = aCenter + vLocal;
+    gl_Position = worldToClip(pos);
+}
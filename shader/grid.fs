@@ -0,0 +1,60 @@
+#version 330
+
+// same order as GridStyle
+#define OFF 0u
+#define LINES 1u
+#define CHECKERBOARD 2u
+
+// same order as the highlights in GridShader
+#define HEAD 0
+#define TURN 1
+#define FOOD 2
+
+uniform float uGridSize;
+uniform vec2 uBoardSize;
+uniform uint uStyle;
+// cell midpoints, only the ones flagged in uHighlightMask are drawn
+uniform vec2 uHighlights[3];
+uniform uint uHighlightMask;
+
+in vec2 vWorld;
+
+void main() {
+    vec2 fragPos = vWorld;
+    // past the edge, only shows when zoomed out further than the board
+    if(any(lessThan(fragPos, vec2(0.))) || any(greaterThan(fragPos, uBoardSize))) {
+        gl_FragColor = vec4(uBackground.rgb * 0.5, 1.);
+        return;
+    }
+
+    for(int i = 0; i < 3; i++) {
+        if((uHighlightMask & (1u << uint(i))) == 0u) {
+            continue;
+        }
+        vec2 dst = abs(fragPos - uHighlights[i]);
+        float halfSize = uGridSize / 2.;
+        if(max(dst.x, dst.y) < halfSize) {
+            vec3 color = i == HEAD ? uSnakeHead.rgb : i == TURN ? uUiAccent.rgb : uUiWarning.rgb;
+            // outlined, with a faint fill
+            float outline = step(halfSize - 2., max(dst.x, dst.y));
+            gl_FragColor = vec4(mix(mix(uBackground.rgb, color, 0.25), color, outline), 1.);
+            return;
+        }
+    }
+
+    vec2 cell = floor(fragPos / uGridSize);
+    vec2 inCell = fragPos - cell * uGridSize;
+    switch(uStyle) {
+        case LINES :
+            // a pixel wide whatever the zoom
+            if(min(inCell.x, inCell.y) * uZoom >= 1.) discard;
+            gl_FragColor = vec4(uGridLine.rgb, 1.);
+            break;
+        case CHECKERBOARD :
+            if(mod(cell.x + cell.y, 2.) < 1.) discard;
+            gl_FragColor = vec4(uGridLine.rgb, 1.);
+            break;
+        default :
+            discard;
+    }
+}
@@ -0,0 +1,14 @@
+#version 330
+
+in vec2 vLocal;
+flat in float vHalfSize;
+
+void main() {
+    vec2 dst = abs(vLocal);
+    if(max(dst.x, dst.y) >= vHalfSize) {
+        discard;
+    }
+    // darker rim around a lighter core
+    float rim = step(vHalfSize * 0.7, max(dst.x, dst.y));
+    gl_FragColor = vec4(mix(uObstacle.rgb, uObstacle.rgb * 0.5, rim), 1.);
+}
@@ -0,0 +1,17 @@
+#version 330
+layout (location = 0) in vec3 aPosition;
+// per obstacle, in world units
+layout (location = 1) in vec2 aCenter;
+// smaller while it grows in
+layout (location = 2) in float aHalfSize;
+
+// from the center, in world units
+out vec2 vLocal;
+flat out float vHalfSize;
+
+void main() {
+    vHalfSize = aHalfSize;
+    vLocal = aPosition.xy * aHalfSize;
+    vec2 pos = aCenter + vLocal;
+    gl_Position = worldToClip(pos);
+}
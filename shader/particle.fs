@@ -0,0 +1,13 @@
+#version 330
+
+in vec2 vLocal;
+in vec4 vColor;
+
+void main() {
+    float dst = length(vLocal);
+    if(dst > 1.) {
+        discard;
+    }
+    // soft dot, brightest in the middle
+    gl_FragColor = vec4(vColor.rgb, vColor.a * (1. - dst * dst));
+}
@@ -0,0 +1,16 @@
+#version 330
+layout (location = 0) in vec3 aPosition;
+// per particle
+layout (location = 1) in vec2 aOffset;
+layout (location = 2) in float aSize;
+layout (location = 3) in vec4 aColor;
+
+out vec2 vLocal;
+out vec4 vColor;
+
+void main() {
+    vLocal = aPosition.xy;
+    vColor = aColor;
+    vec2 pos = aOffset + aPosition.xy * aSize;
+    gl_Position = worldToClip(pos);
+}
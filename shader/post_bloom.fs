@@ -0,0 +1,12 @@
+#version 330
+#define INTENSITY 1.2
+
+uniform sampler2D uScene;
+uniform sampler2D uBloom;
+uniform vec2 uResolution;
+
+void main() {
+    vec2 uv = gl_FragCoord.xy / uResolution;
+    vec3 color = texture(uScene, uv).rgb + texture(uBloom, uv).rgb * INTENSITY;
+    gl_FragColor = vec4(color, 1.);
+}
@@ -0,0 +1,18 @@
+#version 330
+
+uniform sampler2D uScene;
+uniform vec2 uResolution;
+// one texel along the blurred axis
+uniform vec2 uDirection;
+
+const float WEIGHTS[5] = float[](0.227027, 0.1945946, 0.1216216, 0.054054, 0.016216);
+
+void main() {
+    vec2 uv = gl_FragCoord.xy / uResolution;
+    vec3 color = texture(uScene, uv).rgb * WEIGHTS[0];
+    for(int i = 1; i < 5; i++) {
+        color += texture(uScene, uv + uDirection * float(i)).rgb * WEIGHTS[i];
+        color += texture(uScene, uv - uDirection * float(i)).rgb * WEIGHTS[i];
+    }
+    gl_FragColor = vec4(color, 1.);
+}
@@ -0,0 +1,11 @@
+#version 330
+
+uniform sampler2D uScene;
+uniform vec2 uResolution;
+
+// keeps only what is bright enough to glow
+void main() {
+    vec3 color = texture(uScene, gl_FragCoord.xy / uResolution).rgb;
+    float brightness = max(color.r, max(color.g, color.b));
+    gl_FragColor = vec4(color * smoothstep(0.75, 1., brightness), 1.);
+}
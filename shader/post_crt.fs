@@ -0,0 +1,28 @@
+#version 330
+#define CURVATURE 0.08
+#define SCANLINE_PERIOD 3.
+
+uniform sampler2D uScene;
+uniform vec2 uResolution;
+uniform float uTime;
+
+void main() {
+    // bulge the picture like a curved screen
+    vec2 uv = gl_FragCoord.xy / uResolution * 2. - 1.;
+    uv *= 1. + CURVATURE * dot(uv.yx, uv.yx);
+    uv = uv * 0.5 + 0.5;
+    if(uv.x < 0. || uv.x > 1. || uv.y < 0. || uv.y > 1.) {
+        gl_FragColor = vec4(0., 0., 0., 1.);
+        return;
+    }
+
+    // slight color fringing
+    float shift = 0.75 / uResolution.x;
+    vec3 color = vec3(
+        texture(uScene, uv + vec2(shift, 0.)).r,
+        texture(uScene, uv).g,
+        texture(uScene, uv - vec2(shift, 0.)).b
+    );
+    float scanline = 0.8 + 0.2 * sin((uv.y * uResolution.y + uTime * 20.) * 6.2831 / SCANLINE_PERIOD);
+    gl_FragColor = vec4(color * scanline, 1.);
+}
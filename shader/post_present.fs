@@ -0,0 +1,11 @@
+#version 330
+
+uniform sampler2D uScene;
+uniform vec2 uResolution;
+// screen shake, in pixels
+uniform vec2 uOffset;
+
+void main() {
+    vec2 uv = (gl_FragCoord.xy + uOffset) / uResolution;
+    gl_FragColor = vec4(texture(uScene, uv).rgb, 1.);
+}
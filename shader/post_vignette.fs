@@ -0,0 +1,12 @@
+#version 330
+#define STRENGTH 0.45
+
+uniform sampler2D uScene;
+uniform vec2 uResolution;
+
+void main() {
+    vec2 uv = gl_FragCoord.xy / uResolution;
+    vec3 color = texture(uScene, uv).rgb;
+    float dst = length(uv - 0.5) * 1.41421;
+    gl_FragColor = vec4(color * (1. - STRENGTH * smoothstep(0.4, 1., dst)), 1.);
+}
@@ -0,0 +1,50 @@
+#version 330
+
+// same order as PowerUpKind
+#define SLOW_MOTION 0u
+#define SPEED_BOOST 1u
+#define GHOST 2u
+#define SHRINK 3u
+#define SCORE_MULTIPLIER 4u
+
+in vec2 vLocal;
+flat in float vRadius;
+flat in float vTime;
+flat in uint vKind;
+
+void main(){
+    vec2 local = vLocal;
+    float dst = length(local);
+    float pulse = 0.5 + 0.5 * sin(vTime * 6.);
+    vec3 color = uPowerUp[vKind].rgb;
+    // lighter shade some kinds pulse towards
+    vec3 light = mix(color, vec3(1.), 0.5);
+
+    switch(vKind){
+        case SLOW_MOTION :
+            // ring
+            if(dst > vRadius || dst < vRadius * 0.55) discard;
+            break;
+        case SPEED_BOOST :
+            // diamond
+            if(abs(local.x) + abs(local.y) > vRadius) discard;
+            color = mix(color, light, pulse);
+            break;
+        case GHOST :
+            // circle with a scanline pattern so it reads as see-through, as tight on screen whatever the zoom
+            if(dst > vRadius || mod(local.y * uZoom, 3.) < 1.) discard;
+            break;
+        case SHRINK :
+            // square that breathes
+            if(max(abs(local.x), abs(local.y)) > vRadius * (0.55 + 0.25 * pulse)) discard;
+            break;
+        case SCORE_MULTIPLIER :
+            // plus sign
+            if(max(abs(local.x), abs(local.y)) > vRadius || min(abs(local.x), abs(local.y)) > vRadius * 0.3) discard;
+            color = mix(color, light, pulse);
+            break;
+        default :
+            discard;
+    }
+    gl_FragColor = vec4(color, 1.);
+}
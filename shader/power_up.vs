@@ -0,0 +1,24 @@
+#version 330
+layout (location = 0) in vec3 aPosition;
+// per power up, in world units
+layout (location = 1) in vec2 aCenter;
+layout (location = 2) in float aRadius;
+// seconds, drives the pulse
+layout (location = 3) in float aTime;
+// same order as PowerUpKind
+layout (location = 4) in float aKind;
+
+// from the center, in world units
+out vec2 vLocal;
+flat out float vRadius;
+flat out float vTime;
+flat out uint vKind;
+
+void main() {
+    vRadius = aRadius;
+    vTime = aTime;
+    vKind = uint(aKind);
+    vLocal = aPosition.xy * aRadius;
+    vec2 pos = aCenter + vLocal;
+    gl_Position = worldToClip(pos);
+}
@@ -0,0 +1,13 @@
+#version 330
+layout (location = 0) in vec3 aPosition;
+
+// world area the quad covers, bottom left then top right corner
+uniform vec4 uBounds;
+
+// where the fragment is on the board, in world units
+out vec2 vWorld;
+
+void main() {
+    vWorld = mix(uBounds.xy, uBounds.zw, aPosition.xy * 0.5 + 0.5);
+    gl_Position = worldToClip(vWorld);
+}
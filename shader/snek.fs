@@ -1,10 +1,10 @@
 #version 330
-#define MAX_KEYPOINTS 100
+#define MAX_KEYPOINTS 200
 
-#define UP 0
-#define RIGHT 1
-#define DOWN 2
-#define LEFT 3
+#define UP 0u
+#define RIGHT 1u
+#define DOWN 2u
+#define LEFT 3u
 
 precision mediump float;
 
@@ -20,13 +20,15 @@ uniform float uLength;
 uniform MoveKeypoint[MAX_KEYPOINTS] uKeypoints;
 uniform uint uKeypointLen;
 
+in vec2 vWorld;
+
 bool pointInRadius(vec2 pos, vec2 center);
 bool pointInBox(vec2 pos, vec2 boxStart, vec2 boxEnd);
 vec4 getColor(float fac, float min, float max);
 
 
 void main() {
-    vec2 frag_pos = vec2(gl_FragCoord.x, gl_FragCoord.y);
+    vec2 frag_pos = vWorld;
 
     float remainLength = uLength; 
 
@@ -125,6 +127,7 @@ bool pointInBox(vec2 pos, vec2 boxStart, vec2 boxEnd) {
         (pos.y > boxStart.y && pos.y < boxEnd.y);
 }
 
+// blends from the tail color at 0 to the head color at 1
 vec4 getColor(float fac, float min, float max){
-    return vec4(clamp(fac, min, max),  1., clamp(fac, min, max), 1.0);
+    return vec4(mix(uSnakeTail.rgb, uSnakeHead.rgb, clamp(fac, min, max)), 1.0);
 }
\ No newline at end of file
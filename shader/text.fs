@@ -0,0 +1,29 @@
+#version 330
+#define MAX_CHARS 48
+// glyphs are 3x5 pixels, followed by one pixel of spacing
+#define GLYPH_W 3
+#define GLYPH_H 5
+#define ADVANCE 4
+
+uniform vec2 uOrigin;
+uniform float uScale;
+uniform vec3 uColor;
+uniform uint uGlyphs[MAX_CHARS];
+uniform uint uLen;
+
+void main(){
+    // origin is the top left corner of the text, rows go down from there
+    int col = int(floor((gl_FragCoord.x - uOrigin.x) / uScale));
+    int row = int(floor((uOrigin.y - gl_FragCoord.y) / uScale));
+    int idx = col / ADVANCE;
+    col = col - idx * ADVANCE;
+
+    if(gl_FragCoord.x < uOrigin.x || gl_FragCoord.y > uOrigin.y || row >= GLYPH_H || col >= GLYPH_W || idx >= int(uLen)){
+        discard;
+    }
+    uint bit = uint(GLYPH_W * GLYPH_H - 1 - (row * GLYPH_W + col));
+    if(((uGlyphs[idx] >> bit) & 1u) == 0u){
+        discard;
+    }
+    gl_FragColor = vec4(uColor, 1.);
+}
@@ -0,0 +1,16 @@
+// added to every fragment shader right after the version line, filled from the selected theme
+layout(std140) uniform Theme {
+    vec4 uBackground;
+    vec4 uGridLine;
+    vec4 uSnakeHead;
+    vec4 uSnakeTail;
+    // same order as FoodKind
+    vec4 uFood[4];
+    vec4 uFoodHighlight;
+    // same order as PowerUpKind
+    vec4 uPowerUp[5];
+    vec4 uObstacle;
+    vec4 uUiText;
+    vec4 uUiAccent;
+    vec4 uUiWarning;
+};
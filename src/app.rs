@@ -1,27 +1,149 @@
-use std::{collections::HashMap, time::{self, Duration}};
+use std::{cell::RefCell, collections::{HashMap, VecDeque}, fs, path::{Path, PathBuf}, rc::Rc, time::{self, Duration}};
 
 use glow::{COLOR_BUFFER_BIT, HasContext};
 
 mod app_bootstraper;
+pub mod assets;
 pub mod app_owned_data;
 pub mod collider;
+pub mod debug_overlay;
 pub mod board;
+pub mod camera;
+pub mod gamepad;
+pub mod headless;
+mod minimap;
+pub mod post_process;
+pub mod profiler;
+pub mod recording;
+pub mod theme;
+mod title;
 
 pub use app_bootstraper::AppBootstraper;
-pub use collider::ColliderLayer;
+pub use collider::{ColliderLayer, CollisionMatrix};
 
-use crate::{app::{app_owned_data::{AppOwnedData, Time}, board::Board}, WINDOW_HEIGHT, WINDOW_WIDTH};
+use crate::{app::{app_owned_data::{AppOwnedData, InputEvent, Time}, assets::Assets, board::Board, camera::{Camera, CameraBuffer, MAX_ZOOM, MIN_ZOOM}, debug_overlay::{DebugMarker, DebugOverlay}, minimap::Minimap, gamepad::{Direction, Gamepad, GamepadEvent}, post_process::{PostPass, PostProcess, Shake}, profiler::{FrameTimes, Profiler}, recording::{RecordedFrame, Recording}, theme::{Theme, ThemeBuffer}, title::Title}, audio::{Audio, AudioBackend, Volumes}, gl_object, log, objects::{game_rng::GameRng, settings::Settings}, GRID_SIZE, SAVE_PATH, WINDOW_HEIGHT, WINDOW_WIDTH, error::Error};
+
+// zoom in & out by that much per key press
+const ZOOM_STEP: f32 = 1.25;
+
+/// how the app starts, filled from the command line
+pub struct AppConfig {
+    /// window size in pixels
+    pub width: u16,
+    pub height: u16,
+    /// size of a board cell in pixels
+    pub grid_size: u16,
+    /// columns & rows of the board, as many as fit in the window otherwise
+    pub board: Option<(u16, u16)>,
+    /// print a frame rate sample every 50 frames & the average on exit
+    pub record_fps: bool,
+    /// go straight into a run, no title screen
+    pub skip_title: bool,
+    /// every frame lasts this long whatever time it really took, eg for headless runs
+    pub fixed_delta: Option<Duration>,
+    /// the first run is written there once it is over or on quit
+    pub record: Option<PathBuf>,
+    /// played back frame by frame, the player only gets to watch
+    pub replay: Option<Recording>,
+    /// every run starts from this save instead of a new board, eg for benchmark scenarios
+    pub start_from: Option<String>,
+    /// measure every frame, see `App::frame_times`
+    pub profile: bool,
+    /// where the sound goes, headless runs & benchmarks throw it away
+    pub audio: AudioBackend,
+    pub settings: Settings,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            width: WINDOW_WIDTH,
+            height: WINDOW_HEIGHT,
+            grid_size: GRID_SIZE,
+            board: None,
+            record_fps: false,
+            skip_title: false,
+            fixed_delta: None,
+            record: None,
+            replay: None,
+            start_from: None,
+            profile: false,
+            audio: AudioBackend::default(),
+            settings: Settings::default(),
+        }
+    }
+}
+
+impl AppConfig {
+    /// play back `replay` on the board it was recorded on
+    pub fn replaying(replay: Recording) -> Self {
+        let settings = Settings { mode: replay.mode, seed: Some(replay.seed), speed: replay.speed, bot: replay.bot, persist: false, ..Settings::default() };
+        Self {
+            width: replay.width,
+            height: replay.height,
+            grid_size: replay.grid_size,
+            board: replay.cells,
+            skip_title: true,
+            replay: Some(replay),
+            settings,
+            ..Self::default()
+        }
+    }
+}
+
+// the run being recorded & the turns since the last frame
+struct Recorder {
+    path: PathBuf,
+    recording: Recording,
+    turns: Vec<Direction>,
+}
 
 pub struct App {
-    pub gl: glow::Context,
+    pub gl: Rc<glow::Context>,
+    /// shaders & meshes of the game objects, kept across restarts so they are only built once
+    pub assets: Assets,
     t_0: time::SystemTime,
     t_last_render: time::SystemTime,
 
     updateable_ids: Vec<usize>,
     input_listener_ids: Vec<usize>,
     collider_ids: Vec<usize>,
+    /// which collider layers get tested against each other, filled by `on_app_init`
+    pub collision_matrix: CollisionMatrix,
 
     board : Board,
+    // board width, height & cell size in pixels, the board gets rebuilt from them
+    board_size : (u16, u16, u16),
+    /// follows the snek, kept across restarts so the zoom & position carry over
+    pub camera : Rc<RefCell<Camera>>,
+    camera_buffer : CameraBuffer,
+    minimap : Minimap,
+    /// kept across restarts, handed to the game objects by `on_app_init`
+    pub settings : Rc<RefCell<Settings>>,
+    /// fed by the game objects, kept across restarts so a death still shakes
+    pub shake : Rc<RefCell<Shake>>,
+    /// pushed by the game objects every tick while the debug overlay is on
+    pub debug_markers : Rc<RefCell<Vec<DebugMarker>>>,
+    /// played by the game objects, kept across restarts so a death sound isn't cut short
+    pub audio : Rc<RefCell<Audio>>,
+    post_process : PostProcess,
+    theme_buffer : ThemeBuffer,
+    debug_overlay : DebugOverlay,
+    // shown until a run gets picked, nothing ticks meanwhile
+    title : Option<Title>,
+    /// saved run picked from the title screen, taken by `on_app_init`
+    pub resume : Option<String>,
+    /// set by `on_app_init`, gives the run to save on quit or None once it is over
+    pub save_run : Option<Box<dyn Fn() -> Option<String>>>,
+    gamepad : Gamepad,
+    paused : bool,
+    fixed_delta : Option<Duration>,
+    recorder : Option<Recorder>,
+    replay : Option<VecDeque<RecordedFrame>>,
+    // the replay ran out
+    done : bool,
+    start_from : Option<String>,
+    profiler : Option<Profiler>,
     owned_data: HashMap<usize, AppOwnedData>,
     owned_data_counter: usize,
 
@@ -29,12 +151,34 @@ pub struct App {
     record_fps : bool,
     render_count : usize,
 
-    on_app_init : fn(&mut Self)
+    on_app_init : fn(&mut Self) -> Result<(), Error>
 }
 
 impl App {
-    pub fn new(gl: glow::Context, on_app_init : fn(&mut Self)) -> Self {
+    pub fn new(gl: glow::Context, on_app_init : fn(&mut Self) -> Result<(), Error>, config: AppConfig) -> Result<Self, Error> {
+        let AppConfig { width, height, grid_size, board, record_fps, skip_title, fixed_delta, record, replay, start_from, profile, audio, mut settings } = config;
+        let gl = Rc::new(gl);
+        let post_process = PostProcess::new(&gl, width.into(), height.into())?;
+        let theme_buffer = ThemeBuffer::new(&gl)?;
+        let debug_overlay = DebugOverlay::new(&gl)?;
+        let camera_buffer = CameraBuffer::new(&gl)?;
+        let minimap = Minimap::new(&gl)?;
+        let profiler = profile.then(|| Profiler::new(&gl));
+        let title = (!skip_title).then(|| Title::new(&gl, Path::new(SAVE_PATH).exists())).transpose()?;
+        let recorder = record.map(|path| {
+            // the recording can't be played back without its seed
+            let seed = *settings.seed.get_or_insert_with(|| GameRng::from_time().state());
+            Recorder {
+                path,
+                recording: Recording { width, height, grid_size, cells: board, mode: settings.mode, seed, speed: settings.speed, bot: settings.bot, frames: Vec::new() },
+                turns: Vec::new(),
+            }
+        });
+        let (board_width, board_height) = board.map_or((width, height), |(cols, rows)| (cols * grid_size, rows * grid_size));
+        let board = Board::new(board_width, board_height, grid_size);
+        let camera = Camera::new(width.into(), height.into(), &board);
         let mut _self = Self {
+            assets: Assets::new(&gl),
             gl,
             t_last_render: time::SystemTime::now(),
             t_0: time::SystemTime::now(),
@@ -42,27 +186,53 @@ impl App {
             updateable_ids: Vec::new(),
             input_listener_ids: Vec::new(),
             collider_ids: Vec::new(),
+            collision_matrix: CollisionMatrix::default(),
 
-            board : Board::new(WINDOW_WIDTH, WINDOW_HEIGHT, 25),
+            board,
+            board_size : (board_width, board_height, grid_size),
+            camera : Rc::new(RefCell::new(camera)),
+            camera_buffer,
+            minimap,
+            settings : Rc::new(RefCell::new(settings)),
+            shake : Rc::new(RefCell::new(Shake::default())),
+            debug_markers : Rc::new(RefCell::new(Vec::new())),
+            audio : Rc::new(RefCell::new(Audio::new(&audio))),
+            post_process,
+            theme_buffer,
+            debug_overlay,
+            title,
+            resume : start_from.clone(),
+            save_run : None,
+            gamepad : Gamepad::system_default(),
+            paused : false,
+            fixed_delta,
+            recorder,
+            replay : replay.map(|replay| replay.frames.into()),
+            done : false,
+            start_from,
+            profiler,
             owned_data: HashMap::new(),
             owned_data_counter: 0,
 
             fps: Vec::with_capacity(100),
-            record_fps : false,
+            record_fps,
             render_count : 0,
             on_app_init,
         };
 
-        on_app_init(&mut _self);
+        on_app_init(&mut _self)?;
         _self.after_on_app_init();
+        _self.snap_camera();
 
-        _self
+        Ok(_self)
     }
 
     fn clear(&mut self) {
         self.updateable_ids.clear();
         self.input_listener_ids.clear();
         self.collider_ids.clear();
+        self.collision_matrix = CollisionMatrix::default();
+        self.save_run = None;
         self.owned_data.clear();
         self.owned_data_counter = 0;
         self.fps.clear();
@@ -70,24 +240,42 @@ impl App {
     }
 
     fn after_on_app_init(&mut self) {
-        for idx in &mut self.updateable_ids {
+        // every owned data is setupable, not only the updateable ones
+        for idx in 0..self.owned_data_counter {
             self.owned_data
                 .get_mut(&idx)
-                .expect("updateable ids should always updated to match existing item")
-                .as_updateable()
-                .expect("updateable ids should always fetch updateable from owned data")
-                .on_setup(&self.gl, *idx, &self.board);       
+                .expect("owned data counter should always match existing item")
+                .as_setupable()
+                .expect("owned data should always fetch setupable")
+                .on_setup(&self.gl, idx, &self.board);
         }
     }
 
-    fn on_game_over(&mut self) {
+    fn on_game_over(&mut self) -> Result<(), Error> {
+        // only the first run is recorded
+        self.finish_recording();
         // reset everything
         self.clear();
-        self.board = Board::new(WINDOW_WIDTH, WINDOW_HEIGHT, 25);
+        log::event!(Info, "app", "new run", mode = self.settings.borrow().mode.name());
+        if self.resume.is_none() {
+            self.resume = self.start_from.clone();
+        }
+        let (width, height, grid_size) = self.board_size;
+        self.board = Board::new(width, height, grid_size);
+        self.paused = false;
         self.t_0 = time::SystemTime::now();
         self.t_last_render = time::SystemTime::now();
-        (self.on_app_init)(self);
+        (self.on_app_init)(self)?;
         self.after_on_app_init();
+        self.snap_camera();
+        gl_object::log_live("new run");
+        Ok(())
+    }
+
+    // straight onto the snek, a new run doesn't pan over from where the last one ended
+    fn snap_camera(&mut self) {
+        let zoom = self.settings.borrow().zoom;
+        self.camera.borrow_mut().snap(&self.board, zoom);
     }
 
     // become owner of taken data
@@ -108,29 +296,122 @@ impl App {
         self.owned_data_counter += 1;
     }
 
-    pub fn render(&mut self) {
+    /// draw the next frame, fails when a restart can't rebuild the game objects
+    pub fn render(&mut self) -> Result<(), Error> {
+        let _span = log::span!(Trace, "render", "frame", n = self.render_count + 1);
+        if let Some(replay) = self.replay.as_mut() {
+            let Some(frame) = replay.pop_front() else {
+                self.done = true;
+                return Ok(());
+            };
+            for dir in frame.turns {
+                self.dispatch_input(InputEvent::Gamepad(GamepadEvent::Direction(dir)));
+            }
+            let res = self.step(frame.delta);
+            self.advance_audio(frame.delta);
+            return res;
+        }
+        // the whole batch goes through, what comes after a restart is meant for the new run
+        for event in self.gamepad.poll() {
+            if self.title.is_some() {
+                match event {
+                    GamepadEvent::Start => self.leave_title(false)?,
+                    GamepadEvent::Select => self.leave_title(true)?,
+                    _ => {},
+                }
+                continue;
+            }
+            match event {
+                GamepadEvent::Start => self.paused = !self.paused,
+                GamepadEvent::Select => self.on_game_over()?,
+                _ => self.dispatch_input(InputEvent::Gamepad(event)),
+            }
+        }
+
+        let real_delta = self.calc_delta();
+        // a paused frame is still drawn, it just doesn't move anything
+        let delta = if self.paused { Duration::ZERO } else { real_delta };
+        if let Some(recorder) = self.recorder.as_mut() {
+            let turns = std::mem::take(&mut recorder.turns);
+            recorder.recording.frames.push(RecordedFrame { delta, turns });
+        }
+        let res = self.step(delta);
+        // sounds keep playing through a pause
+        self.advance_audio(real_delta);
+        res
+    }
+
+    // mix the sounds played during the frame
+    fn advance_audio(&mut self, delta: Duration) {
+        let volumes = self.settings.borrow().volumes;
+        self.audio.borrow_mut().advance(delta, &volumes, self.paused);
+    }
+
+    // draw a frame, ticking the game objects `delta` forward
+    fn step(&mut self, delta: Duration) -> Result<(), Error> {
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.begin_frame(&self.gl);
+        }
+        let res = self.draw_frame(delta);
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.end_frame(&self.gl);
+        }
+        res
+    }
+
+    /// every frame measured since the start, None unless `AppConfig::profile` is set <br/>
+    /// waits for the gpu to catch up
+    pub fn frame_times(&mut self) -> Option<Vec<FrameTimes>> {
+        self.profiler.as_mut().map(|profiler| profiler.finish(&self.gl))
+    }
+
+    /// whether `frame_times` comes with gpu times
+    pub fn has_gpu_timer(&self) -> bool {
+        self.profiler.as_ref().is_some_and(|profiler| profiler.has_gpu_timer())
+    }
+
+    fn draw_frame(&mut self, delta: Duration) -> Result<(), Error> {
         self.render_count +=1;
-        let delta = self.calc_delta();
         let time =  Time{
                     delta : &delta, 
                     elapsed : &self.elapsed(),
                 };
 
-        if self.record_fps {
+        // the debug overlay shows the latest sample, paused frames don't take any time & give none
+        if (self.record_fps || self.settings.borrow().debug_overlay) && !delta.is_zero() {
             let fps = Duration::from_secs(1).div_duration_f32(delta);
-            if self.render_count % 50 == 0 {
-                println!("sampled : {fps} fps");
+            if self.render_count.is_multiple_of(50) {
+                if self.record_fps {
+                    println!("sampled : {fps} fps");
+                }
                 self.fps.push(fps);
             }
         }
 
+        self.post_process.begin(&self.gl);
+        // uploaded every frame so switching theme shows right away
+        let theme = self.settings.borrow().theme.theme();
+        self.theme_buffer.upload(&self.gl, &theme);
+        // heads to where the snek was last frame
+        let zoom = self.settings.borrow().zoom;
+        self.camera.borrow_mut().update(delta, &self.board, zoom);
+        self.camera_buffer.upload(&self.gl, &self.camera.borrow());
         unsafe {
-            self.gl.clear_color(0., 0.5, 0.5, 1.);
+            let (r, g, b) = theme.background;
+            self.gl.clear_color(r, g, b, 1.);
             self.gl.clear(COLOR_BUFFER_BIT);
         }
+        if let Some(title) = self.title.as_mut() {
+            title.render(&self.gl, self.camera.borrow().view_size(), &theme, self.settings.borrow().mode.name());
+            self.present(delta);
+            return Ok(());
+        }
 
         let mut is_game_over = false;
 
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.begin_phase();
+        }
         for idx in &self.updateable_ids {
             self.owned_data
                 .get_mut(idx)
@@ -139,49 +420,120 @@ impl App {
                 .expect("updateable ids should always fetch updateable from owned data")
                 .on_tick(&self.gl, &time, &self.board, &mut || {is_game_over = true});
         }
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.end_sim();
+        }
         if is_game_over {
+            self.present(delta);
             return self.on_game_over();
         }
-        for (arr_s, idx_a) in self.collider_ids.iter().enumerate() {
-            for idx_b in &self.collider_ids[arr_s+1..] {
-                {
-                    // check a against b from a side
-                    // removing the b to satisfy borrow checker (it cant do multiple borrow at once)
-                    let cldr_b = self.owned_data
-                        .remove(idx_b)
-                        .expect("collider ids should always updated to match existing item");
-                        
-                    let cldr_a = self.owned_data
-                        .get_mut(idx_a)
-                        .expect("collider ids should always updated to match existing item")
-                        .as_collider()
-                        .expect("collider ids should always fetch collider from owned data");
-                    
-                    cldr_a.check_collision(cldr_b.as_ref_collider().expect("collider ids should always fetch collider from owned data"));
-                    self.owned_data.insert(*idx_b, cldr_b);
-                }
-                {
-                    // check a against b from b side
-                    // removing the a to satisfy borrow checker (it cant do multiple borrow at once)
-                    let cldr_a = self.owned_data
-                        .remove(idx_a)
-                        .expect("collider ids should always updated to match existing item");
-                        
-                    let cldr_b = self.owned_data
-                        .get_mut(idx_b)
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.begin_phase();
+        }
+        let collision_span = log::span!(Trace, "collision", "pass", colliders = self.collider_ids.len());
+        check_collisions(&mut self.owned_data, &self.collider_ids, &self.collision_matrix);
+        drop(collision_span);
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.end_collision();
+        }
+        self.draw_minimap(&theme);
+        self.present(delta);
+        Ok(())
+    }
+
+    // the whole board in a corner, only while the view doesn't cover it
+    fn draw_minimap(&mut self, theme: &Theme) {
+        let camera = self.camera.borrow().clone();
+        if !self.settings.borrow().minimap || camera.covers(&self.board) {
+            return;
+        }
+        let overview = Minimap::camera(camera.view_size(), &self.board);
+        self.camera_buffer.upload(&self.gl, &overview);
+        self.minimap.begin(&self.gl, &overview, theme);
+        for idx in &self.updateable_ids {
+            self.owned_data
+                .get_mut(idx)
+                .expect("updateable ids should always updated to match existing item")
+                .as_updateable()
+                .expect("updateable ids should always fetch updateable from owned data")
+                .on_minimap(&self.gl);
+        }
+        self.minimap.finish(&self.gl, &camera, &self.board, theme.ui_text);
+        self.camera_buffer.upload(&self.gl, &camera);
+    }
+
+    // run the offscreen scene through post processing onto the window
+    fn present(&mut self, delta: Duration) {
+        let passes = self.settings.borrow().post_passes.clone();
+        let elapsed = self.elapsed().as_secs_f32();
+        self.post_process.finish(&self.gl, &passes, &mut self.shake.borrow_mut(), delta, elapsed);
+
+        let markers = self.debug_markers.take();
+        if self.settings.borrow().debug_overlay {
+            let colliders = self.collider_ids
+                .iter()
+                .map(|idx| {
+                    let cldr = self.owned_data
+                        .get(idx)
                         .expect("collider ids should always updated to match existing item")
-                        .as_collider()
+                        .as_ref_collider()
                         .expect("collider ids should always fetch collider from owned data");
-                    
-                    cldr_b.check_collision(cldr_a.as_ref_collider().expect("collider ids should always fetch collider from owned data"));
-                    self.owned_data.insert(*idx_a, cldr_a);
-                }
+                    (cldr.layer(), cldr.collider())
+                })
+                .collect::<Vec<_>>();
+            self.debug_overlay.render(&self.gl, &self.camera.borrow(), &colliders, &markers, self.fps.last().copied());
+        }
+    }
+
+    // start the run, `resume` picks up the saved one when there is one
+    fn leave_title(&mut self, resume: bool) -> Result<(), Error> {
+        let Some(title) = self.title.take() else {
+            return Ok(());
+        };
+        if resume && title.can_resume {
+            match fs::read_to_string(SAVE_PATH) {
+                Ok(save) => self.resume = Some(save),
+                Err(err) => log::event!(Error, "app", "can't read the save", path = SAVE_PATH, error = err),
             }
         }
+        self.on_game_over()
+    }
+
+    // write down the recorded run, once
+    fn finish_recording(&mut self) {
+        let Some(recorder) = self.recorder.take() else {
+            return;
+        };
+        if let Err(err) = fs::write(&recorder.path, recorder.recording.to_text()) {
+            log::event!(Error, "app", "can't write the recording", path = recorder.path, error = err);
+        }
+    }
+
+    /// a replay ran out, nothing left to show
+    pub fn is_done(&self) -> bool {
+        self.done
     }
 
     pub fn on_exit(&mut self) {
-        if self.record_fps {
+        self.finish_recording();
+        self.audio.borrow_mut().finish();
+        // a run left on the title screen was never started, the previous save stays
+        if self.title.is_none() && self.settings.borrow().persist {
+            let save = self.save_run.as_ref().and_then(|save_run| save_run());
+            let res = match save {
+                Some(save) => fs::write(SAVE_PATH, save),
+                // over, nothing to resume
+                None => fs::remove_file(SAVE_PATH).or_else(|err| match err.kind() {
+                    std::io::ErrorKind::NotFound => Ok(()),
+                    _ => Err(err),
+                }),
+            };
+            if let Err(err) = res {
+                log::event!(Error, "app", "can't save the run", path = SAVE_PATH, error = err);
+            }
+        }
+        // no sample at all gives no average, eg a run that stayed paused
+        if self.record_fps && !self.fps.is_empty() {
             let fps_len = self.fps.len();
             println!("fps_avg over {} sample : {} fps", fps_len, self.fps.iter().sum::<f32>() / fps_len as f32)
         }
@@ -189,34 +541,273 @@ impl App {
 
     pub fn window_event(
         &mut self,
-        event_loop: &winit::event_loop::ActiveEventLoop,
-        window_id: winit::window::WindowId,
+        _event_loop: &winit::event_loop::ActiveEventLoop,
+        _window_id: winit::window::WindowId,
         event: winit::event::WindowEvent,
-    ) {
-        match event {
-            winit::event::WindowEvent::KeyboardInput { device_id : _, event : _, is_synthetic :_ }  => {
-                for r in &self.input_listener_ids {
-                    self.owned_data
-                        .get_mut(r)
-                        .expect("input listener ids should always updated to match existing item")
-                        .as_input_listener()
-                        .expect("input listener ids should always fetch input listener from owned data")
-                        .on_input(&event, &self.board);
+    ) -> Result<(), Error> {
+        use winit::keyboard::{KeyCode, PhysicalKey};
+
+        if let winit::event::WindowEvent::Resized(size) = &event
+            && size.width != 0
+            && size.height != 0
+        {
+            return self.resize(size.width, size.height);
+        }
+        // a replay only gets watched
+        if self.replay.is_some() {
+            return Ok(());
+        }
+        if let winit::event::WindowEvent::KeyboardInput { device_id : _, event : key_event, is_synthetic :_ } = &event {
+            if self.title.is_some() {
+                match key_event.physical_key {
+                    PhysicalKey::Code(KeyCode::Enter) if key_event.state.is_pressed() => self.leave_title(false)?,
+                    PhysicalKey::Code(KeyCode::KeyC) if key_event.state.is_pressed() => self.leave_title(true)?,
+                    PhysicalKey::Code(KeyCode::KeyM) if key_event.state.is_pressed() => {
+                        let mut settings = self.settings.borrow_mut();
+                        settings.mode = settings.mode.next();
+                    },
+                    _ => {},
                 }
-            },
-            _ => {},
+                return Ok(());
+            }
+            // keyboard counterpart of the gamepad start & select buttons
+            match key_event.physical_key {
+                PhysicalKey::Code(KeyCode::KeyP) if key_event.state.is_pressed() => {
+                    self.paused = !self.paused;
+                },
+                PhysicalKey::Code(KeyCode::KeyR) if key_event.state.is_pressed() => {
+                    self.on_game_over()?;
+                },
+                // switching mode starts a new run right away
+                PhysicalKey::Code(KeyCode::KeyM) if key_event.state.is_pressed() => {
+                    let mut settings = self.settings.borrow_mut();
+                    settings.mode = settings.mode.next();
+                    drop(settings);
+                    self.on_game_over()?;
+                },
+                PhysicalKey::Code(KeyCode::KeyT) if key_event.state.is_pressed() => {
+                    let mut settings = self.settings.borrow_mut();
+                    settings.head_trail = !settings.head_trail;
+                },
+                PhysicalKey::Code(KeyCode::Tab) if key_event.state.is_pressed() => {
+                    let mut settings = self.settings.borrow_mut();
+                    settings.theme = settings.theme.next();
+                },
+                PhysicalKey::Code(KeyCode::KeyG) if key_event.state.is_pressed() => {
+                    let mut settings = self.settings.borrow_mut();
+                    settings.grid = settings.grid.next();
+                },
+                PhysicalKey::Code(KeyCode::F2) if key_event.state.is_pressed() => {
+                    let mut settings = self.settings.borrow_mut();
+                    settings.debug_grid = !settings.debug_grid;
+                },
+                PhysicalKey::Code(KeyCode::F3) if key_event.state.is_pressed() => {
+                    let mut settings = self.settings.borrow_mut();
+                    settings.debug_overlay = !settings.debug_overlay;
+                },
+                PhysicalKey::Code(KeyCode::KeyB) if key_event.state.is_pressed() => {
+                    self.settings.borrow_mut().toggle_pass(PostPass::Bloom);
+                },
+                PhysicalKey::Code(KeyCode::KeyC) if key_event.state.is_pressed() => {
+                    self.settings.borrow_mut().toggle_pass(PostPass::Crt);
+                },
+                PhysicalKey::Code(KeyCode::KeyV) if key_event.state.is_pressed() => {
+                    self.settings.borrow_mut().toggle_pass(PostPass::Vignette);
+                },
+                PhysicalKey::Code(code @ (KeyCode::KeyZ | KeyCode::KeyX)) if key_event.state.is_pressed() => {
+                    let mut settings = self.settings.borrow_mut();
+                    let step = if code == KeyCode::KeyZ { ZOOM_STEP } else { 1. / ZOOM_STEP };
+                    settings.zoom = (settings.zoom * step).clamp(MIN_ZOOM, MAX_ZOOM);
+                },
+                PhysicalKey::Code(KeyCode::KeyL) if key_event.state.is_pressed() => {
+                    let mut settings = self.settings.borrow_mut();
+                    settings.minimap = !settings.minimap;
+                },
+                PhysicalKey::Code(KeyCode::KeyN) if key_event.state.is_pressed() => {
+                    let volumes = &mut self.settings.borrow_mut().volumes;
+                    volumes.muted = !volumes.muted;
+                },
+                // down & up keys side by side, for the master, sfx & music volumes
+                PhysicalKey::Code(code @ (KeyCode::Minus | KeyCode::Equal)) if key_event.state.is_pressed() => {
+                    Volumes::nudge(&mut self.settings.borrow_mut().volumes.master, code == KeyCode::Equal);
+                },
+                PhysicalKey::Code(code @ (KeyCode::BracketLeft | KeyCode::BracketRight)) if key_event.state.is_pressed() => {
+                    Volumes::nudge(&mut self.settings.borrow_mut().volumes.sfx, code == KeyCode::BracketRight);
+                },
+                PhysicalKey::Code(code @ (KeyCode::Semicolon | KeyCode::Quote)) if key_event.state.is_pressed() => {
+                    Volumes::nudge(&mut self.settings.borrow_mut().volumes.music, code == KeyCode::Quote);
+                },
+                _ => self.dispatch_input(InputEvent::Window(event)),
+            }
+        }
+        Ok(())
+    }
+
+    // the board stays the same, the view shows more or less of it
+    fn resize(&mut self, width: u32, height: u32) -> Result<(), Error> {
+        log::event!(Debug, "app", "resized", width = width, height = height);
+        self.post_process.resize(&self.gl, width as i32, height as i32)?;
+        self.camera.borrow_mut().resize(width as f32, height as f32);
+        Ok(())
+    }
+
+    fn dispatch_input(&mut self, event: InputEvent) {
+        if self.paused {
+            if let Some(dir) = event.direction() {
+                log::event!(Debug, "input", "turn ignored", dir = dir, reason = "paused");
+            }
+            return;
+        }
+        if let Some(recorder) = self.recorder.as_mut()
+            && let Some(dir) = event.direction()
+        {
+            recorder.turns.push(dir);
+        }
+        for r in &self.input_listener_ids {
+            self.owned_data
+                .get_mut(r)
+                .expect("input listener ids should always updated to match existing item")
+                .as_input_listener()
+                .expect("input listener ids should always fetch input listener from owned data")
+                .on_input(&event, &self.board);
         }
     }
 
     fn calc_delta(&mut self) -> time::Duration {
+        if let Some(delta) = self.fixed_delta {
+            return delta;
+        }
         let delta = time::SystemTime::now()
             .duration_since(self.t_last_render)
-            .unwrap();
+            // the clock went back, nothing moves this frame
+            .unwrap_or_default();
         self.t_last_render = time::SystemTime::now();
         delta
     }
 
     fn elapsed(&self) -> time::Duration {
-        self.t_0.elapsed().unwrap()
+        self.t_0.elapsed().unwrap_or_default()
+    }
+}
+
+/// self collisions first, then every pair of colliders the matrix lets through, each side on its own
+fn check_collisions(owned_data: &mut HashMap<usize, AppOwnedData>, collider_ids: &[usize], matrix: &CollisionMatrix) {
+    for idx in collider_ids {
+        let cldr = owned_data
+            .get_mut(idx)
+            .expect("collider ids should always updated to match existing item")
+            .as_collider()
+            .expect("collider ids should always fetch collider from owned data");
+        if matrix.allows_self(cldr.layer()) {
+            cldr.check_self_collision();
+        }
+    }
+    for (arr_s, idx_a) in collider_ids.iter().enumerate() {
+        for idx_b in &collider_ids[arr_s+1..] {
+            let (layer_a, layer_b) = (layer_of(owned_data, idx_a), layer_of(owned_data, idx_b));
+            if matrix.allows(layer_a, layer_b) {
+                // check a against b from a side
+                // removing the b to satisfy borrow checker (it cant do multiple borrow at once)
+                let cldr_b = owned_data
+                    .remove(idx_b)
+                    .expect("collider ids should always updated to match existing item");
+                    
+                let cldr_a = owned_data
+                    .get_mut(idx_a)
+                    .expect("collider ids should always updated to match existing item")
+                    .as_collider()
+                    .expect("collider ids should always fetch collider from owned data");
+                
+                cldr_a.check_collision(cldr_b.as_ref_collider().expect("collider ids should always fetch collider from owned data"));
+                owned_data.insert(*idx_b, cldr_b);
+            }
+            if matrix.allows(layer_b, layer_a) {
+                // check a against b from b side
+                // removing the a to satisfy borrow checker (it cant do multiple borrow at once)
+                let cldr_a = owned_data
+                    .remove(idx_a)
+                    .expect("collider ids should always updated to match existing item");
+                    
+                let cldr_b = owned_data
+                    .get_mut(idx_b)
+                    .expect("collider ids should always updated to match existing item")
+                    .as_collider()
+                    .expect("collider ids should always fetch collider from owned data");
+                
+                cldr_b.check_collision(cldr_a.as_ref_collider().expect("collider ids should always fetch collider from owned data"));
+                owned_data.insert(*idx_a, cldr_a);
+            }
+        }
+    }
+}
+
+fn layer_of(owned_data: &HashMap<usize, AppOwnedData>, collider_id: &usize) -> ColliderLayer {
+    owned_data
+        .get(collider_id)
+        .expect("collider ids should always updated to match existing item")
+        .as_ref_collider()
+        .expect("collider ids should always fetch collider from owned data")
+        .layer()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::app::{app_owned_data::{Collider, Setupable}, collider::ColliderType};
+
+    // counts the collisions it gets handed
+    struct Probe {
+        layer: ColliderLayer,
+        checks: Rc<Cell<usize>>,
+    }
+
+    impl Setupable for Probe {
+        fn on_setup(&mut self, _gl: &glow::Context, _registered_idx: usize, _board: &Board) {}
+    }
+
+    impl Collider for Probe {
+        fn check_collision(&mut self, _other: &dyn Collider) {
+            self.checks.set(self.checks.get() + 1);
+        }
+        fn layer(&self) -> ColliderLayer {
+            self.layer
+        }
+        fn collider(&self) -> Vec<ColliderType> {
+            Vec::new()
+        }
+    }
+
+    // the checks each probe got after one pass, in order
+    fn run(matrix: &CollisionMatrix, layers: &[ColliderLayer]) -> Vec<usize> {
+        let mut owned_data = HashMap::new();
+        let checks: Vec<_> = layers.iter().map(|_| Rc::new(Cell::new(0))).collect();
+        for (idx, (layer, checks)) in layers.iter().zip(&checks).enumerate() {
+            let probe: Box<dyn Collider> = Box::new(Probe { layer: *layer, checks: checks.clone() });
+            owned_data.insert(idx, AppOwnedData::from(probe));
+        }
+        let ids: Vec<_> = (0..layers.len()).collect();
+        check_collisions(&mut owned_data, &ids, matrix);
+        checks.iter().map(|checks| checks.get()).collect()
+    }
+
+    #[test]
+    fn allowed_custom_layer_gets_the_player() {
+        let mut matrix = CollisionMatrix::default();
+        let custom = matrix.register_layer();
+        matrix.allow(custom, ColliderLayer::Player);
+        // one way, the player isn't told about it
+        assert_eq!(run(&matrix, &[ColliderLayer::Player, custom]), [0, 1]);
+    }
+
+    #[test]
+    fn custom_layer_gets_nothing_unless_allowed() {
+        let mut matrix = CollisionMatrix::default();
+        let custom = matrix.register_layer();
+        let other = matrix.register_layer();
+        matrix.allow(other, ColliderLayer::Player);
+        assert_ne!(custom, other);
+        assert_eq!(run(&matrix, &[ColliderLayer::Player, custom, other]), [0, 0, 1]);
     }
 }
@@ -1,16 +1,16 @@
-use std::{num::NonZero, time::{self, Duration, SystemTime}};
+use std::{num::NonZero, time::{self, SystemTime}};
 use glutin::{
-    context::NotCurrentContext, config::{Config, ConfigTemplateBuilder, GetGlConfig}, context::{ContextApi, ContextAttributesBuilder, PossiblyCurrentContext, Version}, display::GetGlDisplay, prelude::*, surface::{Surface, WindowSurface}
+    context::NotCurrentContext, config::{Config, ConfigTemplateBuilder, GetGlConfig}, context::{ContextApi, ContextAttributesBuilder, PossiblyCurrentContext, Version}, display::{Display, DisplayApiPreference, GetGlDisplay}, prelude::*, surface::{Surface, WindowSurface}
 };
 use glutin_winit::{DisplayBuilder, GlWindow};
 use winit::{
-    application::ApplicationHandler, dpi::{LogicalSize, PhysicalSize}, error::EventLoopError, event::WindowEvent, event_loop::{ControlFlow, EventLoop}, keyboard::Key, raw_window_handle::HasWindowHandle, window::{Window, WindowAttributes}
+    application::ApplicationHandler, dpi::PhysicalSize, event::WindowEvent, event_loop::{ControlFlow, EventLoop}, raw_window_handle::{HasDisplayHandle, HasWindowHandle}, window::{Window, WindowAttributes}
 };
 
-use crate::{app::App, MAX_FPS, WINDOW_HEIGHT, WINDOW_WIDTH};
+use crate::{app::{App, AppConfig}, error::Error, log, MAX_FPS};
 
 enum GlDisplayCreationState {
-    Unbuilt(DisplayBuilder),
+    Unbuilt(Box<DisplayBuilder>),
     AlreadyBuilt,
 }
 
@@ -25,85 +25,115 @@ pub struct AppBootstraper {
     gl_display: GlDisplayCreationState,
     template: ConfigTemplateBuilder,
     app : Option<App>,
-    on_app_init : fn(&mut App),
+    on_app_init : fn(&mut App) -> Result<(), Error>,
+    // handed to the app once the window is up
+    config : Option<AppConfig>,
+    size : PhysicalSize<u16>,
     last_render : Option<SystemTime>,
+    // the first error met inside the event loop, returned by `exec` once it stopped
+    error : Option<Error>,
 }
 
 const MAX_FPS_PROC:f32 = 1./MAX_FPS;
 
 // responsible for creating & managing window & gl context
 impl AppBootstraper {
-    pub fn new(on_app_init : fn(&mut App)) -> Self {
+    pub fn new(on_app_init : fn(&mut App) -> Result<(), Error>, config: AppConfig) -> Self {
+        let size = PhysicalSize::new(config.width, config.height);
         Self {
             state: None,
             gl_context: None,
             app : None,
-            gl_display: GlDisplayCreationState::Unbuilt(DisplayBuilder::new().with_window_attributes(Some(window_attributes()))),
+            gl_display: GlDisplayCreationState::Unbuilt(Box::new(DisplayBuilder::new().with_window_attributes(Some(window_attributes(size))))),
             template: ConfigTemplateBuilder::default().prefer_hardware_accelerated(Some(true)),
             on_app_init,
+            config: Some(config),
+            size,
             last_render: None,
+            error: None,
         }
     }
 
-    pub fn exec(mut self) -> Result<(), EventLoopError> {
-        let event_loop = EventLoop::new().unwrap();
+    /// runs until the window is closed, or until something fails
+    pub fn exec(mut self) -> Result<(), Error> {
+        let event_loop = EventLoop::new().map_err(|err| Error::Window(err.to_string()))?;
         event_loop.set_control_flow(ControlFlow::Poll);
 
-        event_loop.run_app(&mut self)
-    } 
-}
+        event_loop.run_app(&mut self).map_err(|err| Error::Window(err.to_string()))?;
+        match self.error.take() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
 
-impl ApplicationHandler for AppBootstraper {
-    fn resumed(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
+    // keep the error for `exec` & stop the event loop
+    fn fail(&mut self, event_loop: &winit::event_loop::ActiveEventLoop, err: Error) {
+        log::event!(Debug, "app", "stopping", error = err.to_string());
+        self.error.get_or_insert(err);
+        event_loop.exit();
+    }
+
+    fn resume(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) -> Result<(), Error> {
         let (window, gl_config) = match &self.gl_display {
             GlDisplayCreationState::Unbuilt(display_builder) => {
+                // glutin-winit gives the picker no way to fail, so an empty list is caught before building
+                if !has_gl_config(event_loop, self.template.clone())? {
+                    return Err(Error::NoGlConfig);
+                }
                 let (window, config) = display_builder
                     .clone()
                     .build(event_loop, self.template.clone(), gl_config_picker)
-                    .unwrap();
-                let window = window.unwrap();
+                    .map_err(|err| Error::Window(err.to_string()))?;
+                let window = window.ok_or_else(|| Error::Window("no window was created".to_string()))?;
 
-                self.gl_context = Some(create_gl_context(&window, &config).treat_as_possibly_current());
+                self.gl_context = Some(create_gl_context(&window, &config)?.treat_as_possibly_current());
                 
                 self.gl_display = GlDisplayCreationState::AlreadyBuilt;
 
                 (window, config)
             }
             GlDisplayCreationState::AlreadyBuilt => {
-                let gl_config = self.gl_context.as_ref().unwrap().config();
-                match glutin_winit::finalize_window(event_loop, window_attributes(), &gl_config) {
-                    Ok(window) => (window, gl_config),
-                    Err(_) => {
-                        return event_loop.exit();
-                    }
-                }
+                let gl_config = self.gl_context.as_ref().expect("the context is created with the display").config();
+                let window = glutin_winit::finalize_window(event_loop, window_attributes(self.size), &gl_config)
+                    .map_err(|err| Error::Window(err.to_string()))?;
+                (window, gl_config)
             }
         };
 
-        let attrs = window.build_surface_attributes(Default::default()).unwrap();
+        let attrs = window.build_surface_attributes(Default::default()).map_err(|err| Error::Window(err.to_string()))?;
         let gl_surface = unsafe {
             gl_config
                 .display()
                 .create_window_surface(&gl_config, &attrs)
-                .unwrap()
+                .map_err(|err| Error::Gl("can't create the window surface", err.to_string()))?
         };
 
-        let gl_context = self.gl_context.as_ref().unwrap();
-        gl_context.make_current(&gl_surface).unwrap();
+        let gl_context = self.gl_context.as_ref().expect("the context is created with the display");
+        gl_context.make_current(&gl_surface).map_err(|err| Error::Gl("can't make the GL context current", err.to_string()))?;
 
         //create app
-        self.app.get_or_insert_with(|| {
-            let gl = unsafe { glow::Context::from_loader_function_cstr(|s|self.gl_context.as_ref().unwrap().display().get_proc_address(s)) };
-            let mut app = App::new(gl, self.on_app_init);
+        if self.app.is_none() {
+            let gl = unsafe { glow::Context::from_loader_function_cstr(|s| gl_context.display().get_proc_address(s)) };
+            let config = self.config.take().expect("the app is only created once");
+            let mut app = App::new(gl, self.on_app_init, config)?;
             app.after_on_app_init();
-            app
-        });
+            self.app = Some(app);
+        }
 
         assert!(
             self.state
                 .replace(AppState { gl_surface, window })
                 .is_none()
         );
+        Ok(())
+    }
+}
+
+impl ApplicationHandler for AppBootstraper {
+    fn resumed(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
+        if let Err(err) = self.resume(event_loop) {
+            self.fail(event_loop, err);
+        }
     }
     fn window_event(
         &mut self,
@@ -133,27 +163,34 @@ impl ApplicationHandler for AppBootstraper {
                 device_id: _,
                 event,
                 is_synthetic: _,
-            } => {
-                if event.logical_key
-                    == winit::keyboard::Key::Named(winit::keyboard::NamedKey::Escape)
-                {
-                    event_loop.exit();
-                }
+            } if event.logical_key
+                == winit::keyboard::Key::Named(winit::keyboard::NamedKey::Escape) => {
+                event_loop.exit();
             }
             _=> {},
         }
-        self.app.as_mut().map(|app|app.window_event(event_loop, window_id, event));
+        if let Some(app) = self.app.as_mut()
+            && let Err(err) = app.window_event(event_loop, window_id, event)
+        {
+            self.fail(event_loop, err);
+        }
     }
-    fn exiting(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
+    fn exiting(&mut self, _event_loop: &winit::event_loop::ActiveEventLoop) {
+        if let Some(app) = self.app.as_mut() {
+            app.on_exit();
+        }
+        // its GL objects go while the context is still current
+        self.app = None;
         //clear window
         self.state = None;
-        self.app.as_mut().map(|app| app.on_exit());
 
         #[cfg(egl_backend)]
         {
             //fix for nvidia wayland
-            let _gl_display = self.gl_context.take().unwrap().display();
-            if let glutin::display::Display::Egl(display) = _gl_display {
+            // nothing to clean up when the context never got created
+            if let Some(gl_context) = self.gl_context.take()
+                && let glutin::display::Display::Egl(display) = gl_context.display()
+            {
                 unsafe {
                     display.terminate();
                 }
@@ -163,17 +200,29 @@ impl ApplicationHandler for AppBootstraper {
     fn about_to_wait(&mut self, event_loop: &winit::event_loop::ActiveEventLoop) {
         let last_render = self.last_render.get_or_insert(time::SystemTime::now());
         if let Some(AppState { gl_surface, window }) = self.state.as_ref() 
-            && time::SystemTime::now().duration_since(*last_render).unwrap().as_secs_f32() > MAX_FPS_PROC{
+            && time::SystemTime::now().duration_since(*last_render).unwrap_or_default().as_secs_f32() > MAX_FPS_PROC{
             let _ = self.last_render.insert(SystemTime::now());
-            let gl_context = self.gl_context.as_ref().unwrap();
-            self.app.as_mut().map(|r|r.render());
+            let gl_context = self.gl_context.as_ref().expect("the context is created with the window");
+            let mut res = Ok(());
+            if let Some(app) = self.app.as_mut() {
+                res = app.render();
+                if app.is_done() {
+                    event_loop.exit();
+                }
+            }
             window.request_redraw();
 
-            gl_surface.swap_buffers(gl_context).unwrap();
+            let res = res.and_then(|()| {
+                gl_surface.swap_buffers(gl_context).map_err(|err| Error::Gl("can't swap the window buffers", err.to_string()))
+            });
+            if let Err(err) = res {
+                self.fail(event_loop, err);
+            }
         }
     }
 }
 
+/// `configs` is never empty, see `has_gl_config`
 pub fn gl_config_picker(configs: Box<dyn Iterator<Item = Config> + '_>) -> Config {
     configs
         .reduce(|accum, config| {
@@ -186,18 +235,42 @@ pub fn gl_config_picker(configs: Box<dyn Iterator<Item = Config> + '_>) -> Confi
                 accum
             }
         })
-        .unwrap()
+        .expect("the configs are checked before building")
 }
 
-fn window_attributes() -> WindowAttributes {
+// whether the display `DisplayBuilder` is about to open has any config matching `template`
+fn has_gl_config(event_loop: &winit::event_loop::ActiveEventLoop, template: ConfigTemplateBuilder) -> Result<bool, Error> {
+    let err = |err: String| Error::Gl("can't look for an OpenGL config", err);
+    let handle = event_loop.display_handle().map_err(|e| err(e.to_string()))?.as_raw();
+    let display = unsafe { Display::new(handle, display_preference()) }.map_err(|e| err(e.to_string()))?;
+    let mut configs = unsafe { display.find_configs(template.build()) }.map_err(|e| err(e.to_string()))?;
+    Ok(configs.next().is_some())
+}
+
+// the same backends glutin-winit tries by default
+#[cfg(all(unix, not(target_os = "macos"), not(target_os = "android")))]
+fn display_preference() -> DisplayApiPreference {
+    DisplayApiPreference::GlxThenEgl(Box::new(winit::platform::x11::register_xlib_error_hook))
+}
+#[cfg(windows)]
+fn display_preference() -> DisplayApiPreference {
+    DisplayApiPreference::WglThenEgl(None)
+}
+#[cfg(target_os = "macos")]
+fn display_preference() -> DisplayApiPreference {
+    DisplayApiPreference::Cgl
+}
+
+fn window_attributes(size: PhysicalSize<u16>) -> WindowAttributes {
     Window::default_attributes()
         .with_transparent(true)
         .with_title("GL_Snek")
-        .with_resizable(false)
-        .with_inner_size(PhysicalSize::new(WINDOW_WIDTH, WINDOW_HEIGHT))
+        .with_resizable(true)
+        .with_inner_size(size)
 }
 
-fn create_gl_context(window: &Window, gl_config: &Config) -> NotCurrentContext {
+// tries every context version in turn, the error lists why each one was refused
+fn create_gl_context(window: &Window, gl_config: &Config) -> Result<NotCurrentContext, Error> {
     let raw_window_handle = window.window_handle().ok().map(|wh| wh.as_raw());
 
     // The context creation part.
@@ -220,15 +293,20 @@ fn create_gl_context(window: &Window, gl_config: &Config) -> NotCurrentContext {
     // has to be created.
     let gl_display = gl_config.display();
 
-    unsafe {
-        gl_display.create_context(gl_config, &context_attributes).unwrap_or_else(|_| {
-            gl_display.create_context(gl_config, &fallback_context_attributes).unwrap_or_else(
-                |_| {
-                    gl_display
-                        .create_context(gl_config, &legacy_context_attributes)
-                        .expect("failed to create context")
-                },
-            )
-        })
+    let attempts = [
+        ("OpenGL, the latest available", context_attributes),
+        ("OpenGL ES", fallback_context_attributes),
+        ("OpenGL 3.3", legacy_context_attributes),
+    ];
+    let mut tried = Vec::with_capacity(attempts.len());
+    for (version, attributes) in attempts {
+        match unsafe { gl_display.create_context(gl_config, &attributes) } {
+            Ok(context) => return Ok(context),
+            Err(err) => {
+                log::event!(Info, "app", "context refused", version = version, error = err.to_string());
+                tried.push((version, err));
+            }
+        }
     }
+    Err(Error::GlContext(tried))
 }
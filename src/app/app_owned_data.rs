@@ -1,7 +1,7 @@
 use core::time;
 use std::any::Any;
 
-use crate::app::{board::Board, collider::ColliderType, App, ColliderLayer};
+use crate::app::{board::Board, collider::ColliderType, gamepad::{Direction, GamepadEvent}, ColliderLayer};
 
 pub struct AppOwnedData(Box<dyn Any>);
 
@@ -73,6 +73,7 @@ impl AppOwnedData {
     pub fn as_mut<T: ?Sized + 'static>(&mut self) -> Option<&mut Box<T>> {
         self.0.downcast_mut::<Box<T>>()
     }
+    #[allow(clippy::borrowed_box)]
     pub fn as_ref<T: ?Sized + 'static>(&self) -> Option<&Box<T>> {
         self.0.downcast_ref::<Box<T>>()
     }
@@ -82,11 +83,38 @@ pub trait Setupable {
     fn on_setup(&mut self, gl: &glow::Context, registered_idx:usize, board: &Board);
 }
 
+/// keyboard & gamepad input share the same listener
+pub enum InputEvent {
+    Window(winit::event::WindowEvent),
+    Gamepad(GamepadEvent),
+}
+
+impl InputEvent {
+    /// arrow keys, WASD or the gamepad d-pad & left stick, only on press
+    pub fn direction(&self) -> Option<Direction> {
+        use winit::{event::WindowEvent, keyboard::{KeyCode, PhysicalKey}};
+
+        match self {
+            InputEvent::Window(WindowEvent::KeyboardInput { event, .. }) if event.state.is_pressed() => {
+                match event.physical_key {
+                    PhysicalKey::Code(KeyCode::ArrowUp) | PhysicalKey::Code(KeyCode::KeyW) => Some(Direction::Up),
+                    PhysicalKey::Code(KeyCode::ArrowLeft) | PhysicalKey::Code(KeyCode::KeyA) => Some(Direction::Left),
+                    PhysicalKey::Code(KeyCode::ArrowRight) | PhysicalKey::Code(KeyCode::KeyD) => Some(Direction::Right),
+                    PhysicalKey::Code(KeyCode::ArrowDown) | PhysicalKey::Code(KeyCode::KeyS) => Some(Direction::Down),
+                    _ => None,
+                }
+            }
+            InputEvent::Gamepad(GamepadEvent::Direction(dir)) => Some(*dir),
+            _ => None,
+        }
+    }
+}
+
 pub trait InputListener : Setupable
 where
     Self: 'static,
 {
-    fn on_input(&mut self, event: &winit::event::WindowEvent, board : &Board);
+    fn on_input(&mut self, event: &InputEvent, board : &Board);
 }
 
 pub struct Time<'a> {
@@ -99,14 +127,21 @@ where
 {
     /// Can also render inside this function
     fn on_tick(&mut self, gl: &glow::Context, time : &Time, board: &Board, game_over : &mut dyn FnMut());
+    /// draw again what the tick drew, once every updateable ticked, the minimap camera & viewport are already set <br/>
+    /// only for what's worth seeing from afar, nothing by default
+    fn on_minimap(&mut self, _gl: &glow::Context) {}
 }
 
 pub trait Collider : Setupable
 where
     Self: 'static,
 {
+    /// only called with the layers this one reacts to in the app `CollisionMatrix`
     fn check_collision(&mut self, other: &dyn Collider);
+    /// only called when the layer is allowed to collide with itself, once per frame before the pairs
+    fn check_self_collision(&mut self) {}
     fn layer(&self) -> ColliderLayer;
+    /// the first shape is the primary one, eg the head of the player
     fn collider(&self) -> Vec<ColliderType>;
 }
 
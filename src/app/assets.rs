@@ -0,0 +1,61 @@
+use std::{any::Any, collections::HashMap, rc::Rc};
+
+use crate::{
+    error::Error,
+    log,
+    meshes::{InstancedRect, UnitRect},
+    shaders::Shader,
+};
+
+/// shaders & meshes handed out by name to the game objects, kept across restarts <br/>
+/// the first object asking for a name compiles or builds it, the others share it
+pub struct Assets {
+    gl: Rc<glow::Context>,
+    // one shader type per name, checked on the way out
+    shaders: HashMap<&'static str, Rc<dyn Any>>,
+    unit_rects: HashMap<&'static str, Rc<UnitRect>>,
+    instanced_rects: HashMap<&'static str, Rc<InstancedRect>>,
+}
+
+impl Assets {
+    pub fn new(gl: &Rc<glow::Context>) -> Self {
+        Self { gl: gl.clone(), shaders: HashMap::new(), unit_rects: HashMap::new(), instanced_rects: HashMap::new() }
+    }
+
+    /// `load` runs only when nothing is cached under `name` yet, eg `assets.shader("food", FoodShader::new)` <br/>
+    /// every object sharing it sets the uniforms it needs before each draw
+    pub fn shader<S: Shader + 'static>(&mut self, name: &'static str, load: fn(&Rc<glow::Context>) -> Result<S, Error>) -> Result<Rc<S>, Error> {
+        let shader = match self.shaders.get(name) {
+            Some(shader) => shader.clone(),
+            None => {
+                let shader: Rc<dyn Any> = Rc::new(load(&self.gl)?);
+                log::event!(Debug, "assets", "shader cached", name = name);
+                self.shaders.insert(name, shader.clone());
+                shader
+            }
+        };
+        Ok(shader.downcast::<S>().unwrap_or_else(|_| panic!("the `{name}` shader is asked for as two different types")))
+    }
+
+    /// the rect takes its attribute locations from `shader` the first time
+    pub fn unit_rect(&mut self, name: &'static str, shader: &impl Shader) -> Result<Rc<UnitRect>, Error> {
+        if let Some(mesh) = self.unit_rects.get(name) {
+            return Ok(mesh.clone());
+        }
+        let mesh = Rc::new(UnitRect::new(&self.gl, shader)?);
+        log::event!(Debug, "assets", "mesh cached", name = name);
+        self.unit_rects.insert(name, mesh.clone());
+        Ok(mesh)
+    }
+
+    /// same as `unit_rect`, `layout` only matters the first time too
+    pub fn instanced_rect(&mut self, name: &'static str, shader: &impl Shader, layout: &[(&str, i32)]) -> Result<Rc<InstancedRect>, Error> {
+        if let Some(mesh) = self.instanced_rects.get(name) {
+            return Ok(mesh.clone());
+        }
+        let mesh = Rc::new(InstancedRect::new(&self.gl, shader, layout)?);
+        log::event!(Debug, "assets", "mesh cached", name = name);
+        self.instanced_rects.insert(name, mesh.clone());
+        Ok(mesh)
+    }
+}
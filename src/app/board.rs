@@ -1,3 +1,5 @@
+use rand::Rng;
+
 #[derive(Clone, Debug, PartialEq, Default)]
 pub struct Position {
     pub x: f32,
@@ -38,9 +40,22 @@ impl Board{
         }
     }
 
+    pub fn random_midpt(&self, rng: &mut impl Rng) -> Position {
+        let (y, row) = &self.midpoints[rng.random_range(0..self.midpoints.len())];
+        let x = row[rng.random_range(0..row.len())];
+        Position { x, y: *y }
+    }
+
+    /// every cell, row by row
+    pub fn midpts(&self) -> impl Iterator<Item = Position> + '_ {
+        self.midpoints
+            .iter()
+            .flat_map(|(y, row)| row.iter().map(|x| Position { x: *x, y: *y }))
+    }
+
     pub fn current_midpts(&self, pos : Position) -> Option<Position> {
-        let x_idx = (pos.x / self.grid_size as f32) as usize;
-        let y_idx = (pos.y / self.grid_size as f32) as usize;
+        let x_idx = (pos.x / self.grid_size) as usize;
+        let y_idx = (pos.y / self.grid_size) as usize;
 
         if y_idx < self.midpoints.len() && x_idx < self.midpoints[y_idx].1.len() {
             Some(Position { x: self.midpoints[y_idx].1[x_idx], y: self.midpoints[y_idx].0 })
@@ -0,0 +1,159 @@
+use std::{mem, rc::Rc, time::Duration};
+
+use glow::{HasContext, NativeBuffer};
+
+use crate::{app::board::{Board, Position}, error::Error, gl_object::GlObject};
+
+/// uniform block binding of `Camera`, see `shader/camera.glsl`
+pub const CAMERA_BINDING: u32 = 1;
+pub const MIN_ZOOM: f32 = 0.25;
+pub const MAX_ZOOM: f32 = 4.;
+// how quickly the view catches up with the head & the picked zoom, per second
+const FOLLOW_RATE: f32 = 6.;
+
+/// which part of the board shows & where in the window <br/>
+/// follows a target with some lag, stays on the board unless the whole of it fits
+#[derive(Clone, Debug)]
+pub struct Camera {
+    /// world position shown in the middle of the viewport
+    center: Position,
+    zoom: f32,
+    target: Position,
+    /// window area drawn to, in pixels from the bottom left : x, y, width & height
+    viewport: (f32, f32, f32, f32),
+}
+
+impl Camera {
+    /// the whole window, looking at the middle of the board
+    pub fn new(width: f32, height: f32, board: &Board) -> Self {
+        let middle = Position { x: board.width / 2., y: board.height / 2. };
+        Self { center: middle.clone(), zoom: 1., target: middle, viewport: (0., 0., width, height) }
+    }
+
+    /// the whole board fit in `viewport`, eg for the minimap
+    pub fn overview(viewport: (f32, f32, f32, f32), board: &Board) -> Self {
+        let zoom = f32::min(viewport.2 / board.width, viewport.3 / board.height);
+        let middle = Position { x: board.width / 2., y: board.height / 2. };
+        Self { center: middle.clone(), zoom, target: middle, viewport }
+    }
+
+    /// the whole window again, once it got resized
+    pub fn resize(&mut self, width: f32, height: f32) {
+        self.viewport = (0., 0., width, height);
+    }
+
+    /// where the view heads to from now on
+    pub fn follow(&mut self, at: &Position) {
+        self.target = at.clone();
+    }
+
+    /// closes in on the target & `zoom` over `delta`
+    pub fn update(&mut self, delta: Duration, board: &Board, zoom: f32) {
+        let fac = 1. - (-FOLLOW_RATE * delta.as_secs_f32()).exp();
+        self.zoom += (zoom - self.zoom) * fac;
+        let target = self.clamped(&self.target, board);
+        self.center.x += (target.x - self.center.x) * fac;
+        self.center.y += (target.y - self.center.y) * fac;
+        // a smaller zoom may show past the edge where the center was still fine
+        self.center = self.clamped(&self.center, board);
+    }
+
+    /// straight onto the target & `zoom`, eg on a new run
+    pub fn snap(&mut self, board: &Board, zoom: f32) {
+        self.zoom = zoom;
+        self.center = self.clamped(&self.target, board);
+    }
+
+    // keeps the view on the board, centered on an axis the whole board fits on
+    fn clamped(&self, at: &Position, board: &Board) -> Position {
+        let axis = |at: f32, view: f32, size: f32| {
+            let half = view / self.zoom / 2.;
+            if half * 2. >= size { size / 2. } else { at.clamp(half, size - half) }
+        };
+        Position { x: axis(at.x, self.viewport.2, board.width), y: axis(at.y, self.viewport.3, board.height) }
+    }
+
+    pub fn view_size(&self) -> (f32, f32) {
+        (self.viewport.2, self.viewport.3)
+    }
+
+    pub fn viewport(&self) -> (f32, f32, f32, f32) {
+        self.viewport
+    }
+
+    /// bottom left & top right corners of the world area in view
+    pub fn visible(&self) -> (Position, Position) {
+        let (half_w, half_h) = (self.viewport.2 / self.zoom / 2., self.viewport.3 / self.zoom / 2.);
+        (
+            Position { x: self.center.x - half_w, y: self.center.y - half_h },
+            Position { x: self.center.x + half_w, y: self.center.y + half_h },
+        )
+    }
+
+    /// the whole board is in view, along both axes
+    pub fn covers(&self, board: &Board) -> bool {
+        let (min, max) = self.visible();
+        min.x <= 0. && min.y <= 0. && max.x >= board.width && max.y >= board.height
+    }
+
+    /// some of the view is off the board, along either axis
+    pub fn past_edge(&self, board: &Board) -> bool {
+        let (min, max) = self.visible();
+        min.x < 0. || min.y < 0. || max.x > board.width || max.y > board.height
+    }
+
+    /// window pixel a world position is drawn at
+    pub fn to_screen(&self, at: &Position) -> Position {
+        let (x, y, w, h) = self.viewport;
+        Position { x: (at.x - self.center.x) * self.zoom + x + w / 2., y: (at.y - self.center.y) * self.zoom + y + h / 2. }
+    }
+
+    // laid out like the `Camera` block
+    fn to_std140(&self) -> Vec<f32> {
+        let (_, _, w, h) = self.viewport;
+        let zoom = self.zoom;
+        let mut data = Vec::with_capacity(36);
+        // world to viewport pixels
+        data.extend(affine(zoom, zoom, w / 2. - self.center.x * zoom, h / 2. - self.center.y * zoom));
+        // viewport pixels to clip space
+        data.extend(affine(2. / w, 2. / h, -1., -1.));
+        data.extend([zoom, 0., 0., 0.]);
+        data
+    }
+}
+
+// scales x & y then moves them, column major
+fn affine(scale_x: f32, scale_y: f32, move_x: f32, move_y: f32) -> [f32; 16] {
+    [
+        scale_x, 0., 0., 0.,
+        0., scale_y, 0., 0.,
+        0., 0., 1., 0.,
+        move_x, move_y, 0., 1.,
+    ]
+}
+
+/// uniform buffer every shader reads the camera from, uploaded once per view drawn
+pub struct CameraBuffer {
+    buffer: GlObject<NativeBuffer>,
+}
+
+impl CameraBuffer {
+    pub fn new(gl: &Rc<glow::Context>) -> Result<Self, Error> {
+        let buffer = unsafe {
+            let buffer = GlObject::create(gl, |gl| gl.create_buffer())?;
+            gl.bind_buffer_base(glow::UNIFORM_BUFFER, CAMERA_BINDING, Some(*buffer));
+            buffer
+        };
+        Ok(Self { buffer })
+    }
+    pub fn upload(&self, gl: &glow::Context, camera: &Camera) {
+        let data = camera.to_std140();
+        unsafe {
+            let data = core::slice::from_raw_parts(data.as_ptr() as *const u8, data.len() * mem::size_of::<f32>());
+            gl.bind_buffer(glow::UNIFORM_BUFFER, Some(*self.buffer));
+            gl.buffer_data_u8_slice(glow::UNIFORM_BUFFER, data, glow::DYNAMIC_DRAW);
+            gl.bind_buffer(glow::UNIFORM_BUFFER, None);
+            gl.bind_buffer_base(glow::UNIFORM_BUFFER, CAMERA_BINDING, Some(*self.buffer));
+        }
+    }
+}
@@ -1,18 +1,118 @@
 use crate::app::board::Position;
 
+// builtin layers, the registered ones come after
+const BUILTIN_LAYERS: usize = 4;
+// a layer is a bit of the masks
+const MAX_LAYERS: usize = u64::BITS as usize;
+
 // add new collider here for different type
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ColliderLayer {
     Player,
-    Food
+    Food,
+    PowerUp,
+    Obstacle,
+    /// brought by the game objects themselves, see `CollisionMatrix::register_layer`
+    // none in the game yet
+    #[allow(dead_code)]
+    Custom(u8),
+}
+
+impl ColliderLayer {
+    fn idx(&self) -> usize {
+        match self {
+            Self::Player => 0,
+            Self::Food => 1,
+            Self::PowerUp => 2,
+            Self::Obstacle => 3,
+            Self::Custom(n) => BUILTIN_LAYERS + *n as usize,
+        }
+    }
+    fn bit(&self) -> u64 {
+        1 << self.idx()
+    }
+}
+
+/// which layers react to which, the app skips every other pair <br/>
+/// reset on every restart, the game fills it from `on_app_init`
+pub struct CollisionMatrix {
+    // by layer, the layers it reacts to, one more for every registered layer
+    masks: Vec<u64>,
+    // layers whose colliders are tested against their own shapes
+    self_mask: u64,
+}
+
+impl Default for CollisionMatrix {
+    fn default() -> Self {
+        Self { masks: vec![0; BUILTIN_LAYERS], self_mask: 0 }
+    }
+}
+
+impl CollisionMatrix {
+    /// a new layer, nothing reacts to it until allowed
+    #[allow(dead_code)]
+    pub fn register_layer(&mut self) -> ColliderLayer {
+        assert!(self.masks.len() < MAX_LAYERS, "no more than {MAX_LAYERS} collider layers");
+        let layer = ColliderLayer::Custom((self.masks.len() - BUILTIN_LAYERS) as u8);
+        self.masks.push(0);
+        layer
+    }
+    /// one way, the colliders of `layer` get the ones of `other` in `Collider::check_collision`
+    pub fn allow(&mut self, layer: ColliderLayer, other: ColliderLayer) -> &mut Self {
+        assert!(self.is_registered(other), "{other:?} isn't registered");
+        let Some(mask) = self.masks.get_mut(layer.idx()) else {
+            panic!("{layer:?} isn't registered");
+        };
+        *mask |= other.bit();
+        self
+    }
+    /// eg the snek running into its own body, see `Collider::check_self_collision`
+    pub fn allow_self(&mut self, layer: ColliderLayer) -> &mut Self {
+        assert!(self.is_registered(layer), "{layer:?} isn't registered");
+        self.self_mask |= layer.bit();
+        self
+    }
+    /// whether `layer` reacts to `other`, never for a layer that isn't registered
+    pub fn allows(&self, layer: ColliderLayer, other: ColliderLayer) -> bool {
+        self.is_registered(other) && self.masks.get(layer.idx()).is_some_and(|mask| mask & other.bit() != 0)
+    }
+    pub fn allows_self(&self, layer: ColliderLayer) -> bool {
+        self.is_registered(layer) && self.self_mask & layer.bit() != 0
+    }
+    fn is_registered(&self, layer: ColliderLayer) -> bool {
+        layer.idx() < self.masks.len()
+    }
 }
 
 #[derive(Debug)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum ColliderType {
-    AABB(AABB)
+    AABB(AABB),
+    Circle(Circle),
+    Capsule(Capsule),
+}
+
+impl ColliderType {
+    /// touching counts as intersecting, whatever the pair of shapes
+    pub fn intersects(&self, other: &ColliderType) -> bool {
+        use ColliderType::*;
+        match (self, other) {
+            (AABB(a), AABB(b)) => a.intersects(b),
+            (Circle(c), AABB(b)) | (AABB(b), Circle(c)) => b.distance_to(&c.center) <= c.radius,
+            (Circle(a), Circle(b)) => distance(&a.center, &b.center) <= a.radius + b.radius,
+            (Capsule(c), Circle(o)) | (Circle(o), Capsule(c)) => {
+                point_segment_distance(&o.center, &c.start, &c.end) <= c.radius + o.radius
+            }
+            (Capsule(a), Capsule(b)) => {
+                segment_distance(&a.start, &a.end, &b.start, &b.end) <= a.radius + b.radius
+            }
+            (Capsule(c), AABB(b)) | (AABB(b), Capsule(c)) => b.segment_distance(&c.start, &c.end) <= c.radius,
+        }
+    }
 }
 
 #[derive(Debug)]
+#[allow(clippy::upper_case_acronyms)]
 pub struct AABB {
     min: Position,
     max: Position,
@@ -22,9 +122,99 @@ impl AABB {
     pub fn new(min: Position, max: Position) -> Self {
         AABB { min, max }
     }
+    pub fn min(&self) -> &Position {
+        &self.min
+    }
+    pub fn max(&self) -> &Position {
+        &self.max
+    }
 
     pub fn intersects(&self, other: &AABB) -> bool {
         !(self.max.x < other.min.x || self.min.x > other.max.x ||
           self.max.y < other.min.y || self.min.y > other.max.y)
     }
-}
\ No newline at end of file
+
+    // 0 when inside
+    fn distance_to(&self, p: &Position) -> f32 {
+        let closest = Position {
+            x: p.x.clamp(self.min.x, self.max.x),
+            y: p.y.clamp(self.min.y, self.max.y),
+        };
+        distance(p, &closest)
+    }
+
+    // 0 when the segment starts inside or crosses an edge
+    fn segment_distance(&self, start: &Position, end: &Position) -> f32 {
+        if self.distance_to(start) == 0. {
+            return 0.;
+        }
+        let corners = [
+            Position { x: self.min.x, y: self.min.y },
+            Position { x: self.max.x, y: self.min.y },
+            Position { x: self.max.x, y: self.max.y },
+            Position { x: self.min.x, y: self.max.y },
+        ];
+        (0..4)
+            .map(|i| segment_distance(start, end, &corners[i], &corners[(i + 1) % 4]))
+            .fold(f32::MAX, f32::min)
+    }
+}
+
+/// eg the food
+#[derive(Debug)]
+pub struct Circle {
+    pub center: Position,
+    pub radius: f32,
+}
+
+impl Circle {
+    pub fn new(center: Position, radius: f32) -> Self {
+        Circle { center, radius }
+    }
+}
+
+/// every point within `radius` of the segment from `start` to `end`, eg a straight part of the snek body
+#[derive(Debug)]
+pub struct Capsule {
+    pub start: Position,
+    pub end: Position,
+    pub radius: f32,
+}
+
+impl Capsule {
+    pub fn new(start: Position, end: Position, radius: f32) -> Self {
+        Capsule { start, end, radius }
+    }
+}
+
+fn distance(a: &Position, b: &Position) -> f32 {
+    (a.x - b.x).hypot(a.y - b.y)
+}
+
+fn point_segment_distance(p: &Position, a: &Position, b: &Position) -> f32 {
+    let (abx, aby) = (b.x - a.x, b.y - a.y);
+    let len_sq = abx * abx + aby * aby;
+    // a zero length segment is a point
+    let t = if len_sq == 0. {
+        0.
+    } else {
+        (((p.x - a.x) * abx + (p.y - a.y) * aby) / len_sq).clamp(0., 1.)
+    };
+    distance(p, &Position { x: a.x + abx * t, y: a.y + aby * t })
+}
+
+fn segment_distance(a: &Position, b: &Position, c: &Position, d: &Position) -> f32 {
+    // which side of the line `from` `to` is `p` on
+    let side = |from: &Position, to: &Position, p: &Position| {
+        (to.x - from.x) * (p.y - from.y) - (to.y - from.y) * (p.x - from.x)
+    };
+    let crossing = side(a, b, c) * side(a, b, d) < 0. && side(c, d, a) * side(c, d, b) < 0.;
+    if crossing {
+        return 0.;
+    }
+    // otherwise the closest points always include an end of one of them
+    point_segment_distance(a, c, d)
+        .min(point_segment_distance(b, c, d))
+        .min(point_segment_distance(c, a, b))
+        .min(point_segment_distance(d, a, b))
+}
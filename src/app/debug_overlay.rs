@@ -0,0 +1,113 @@
+use std::rc::Rc;
+
+use crate::{
+    app::{board::Position, camera::Camera, collider::ColliderType, theme::Color, ColliderLayer},
+    error::Error,
+    meshes::{InstancedRect, UnitRect},
+    shaders::{DebugShader, GLYPH_HEIGHT, Shader, TextShader, text_width},
+};
+
+// a negative radius draws a box, see `shader/debug.vs`
+const INSTANCE_LAYOUT: [(&str, i32); 4] = [("aStart", 2), ("aEnd", 2), ("aRadius", 1), ("aColor", 3)];
+const MARGIN: f32 = 8.;
+const TEXT_SCALE: f32 = 2.;
+// half size of the box drawn on a marker
+const MARKER_SIZE: f32 = 3.;
+const MARKER_COLOR: Color = (1., 0.3, 1.);
+const TEXT_COLOR: Color = (1., 1., 1.);
+
+/// fixed so they stay apart whatever the theme
+fn layer_color(layer: ColliderLayer) -> Color {
+    match layer {
+        ColliderLayer::Player => (0.2, 1., 0.2),
+        ColliderLayer::Food => (1., 0.9, 0.1),
+        ColliderLayer::PowerUp => (0.3, 0.6, 1.),
+        ColliderLayer::Obstacle => (1., 0.2, 0.2),
+        ColliderLayer::Custom(_) => (1., 1., 1.),
+    }
+}
+
+/// labelled point pushed by the game objects while the overlay is on, eg where the snek turned
+pub struct DebugMarker {
+    pub at: Position,
+    pub label: String,
+}
+
+/// collider outlines, markers & frame rate drawn over the final image, untouched by post processing
+pub struct DebugOverlay {
+    shader: DebugShader,
+    mesh: InstancedRect,
+    text: TextShader,
+    text_mesh: UnitRect,
+    instances: Vec<f32>,
+}
+
+impl DebugOverlay {
+    pub fn new(gl: &Rc<glow::Context>) -> Result<Self, Error> {
+        let shader = DebugShader::new(gl)?;
+        let mesh = InstancedRect::new(gl, &shader, &INSTANCE_LAYOUT)?;
+        let text = TextShader::new(gl)?;
+        let text_mesh = UnitRect::new(gl, &text)?;
+        text.use_shader(gl);
+        text.set_scale(gl, TEXT_SCALE);
+
+        Ok(Self {
+            shader,
+            mesh,
+            text,
+            text_mesh,
+            instances: Vec::new(),
+        })
+    }
+
+    /// `fps` is the latest sample, if any got recorded yet
+    pub fn render(
+        &mut self,
+        gl: &glow::Context,
+        camera: &Camera,
+        colliders: &[(ColliderLayer, Vec<ColliderType>)],
+        markers: &[DebugMarker],
+        fps: Option<f32>,
+    ) {
+        self.instances.clear();
+        for (layer, shapes) in colliders {
+            let (r, g, b) = layer_color(*layer);
+            for shape in shapes {
+                let (start, end, radius) = match shape {
+                    ColliderType::AABB(aabb) => (aabb.min(), aabb.max(), -1.),
+                    ColliderType::Circle(circle) => (&circle.center, &circle.center, circle.radius),
+                    ColliderType::Capsule(capsule) => (&capsule.start, &capsule.end, capsule.radius),
+                };
+                self.instances.extend([start.x, start.y, end.x, end.y, radius, r, g, b]);
+            }
+        }
+        let (r, g, b) = MARKER_COLOR;
+        for marker in markers {
+            let Position { x, y } = marker.at;
+            self.instances.extend([x - MARKER_SIZE, y - MARKER_SIZE, x + MARKER_SIZE, y + MARKER_SIZE, -1., r, g, b]);
+        }
+        self.shader.use_shader(gl);
+        self.mesh.render(gl, &self.instances);
+
+        self.text.use_shader(gl);
+        for marker in markers {
+            // text is laid out in window pixels, it doesn't scale with the zoom
+            let at = camera.to_screen(&marker.at);
+            let top = at.y + MARKER_SIZE + (GLYPH_HEIGHT + 1.) * TEXT_SCALE;
+            self.draw_text(gl, &marker.label, at.x + MARKER_SIZE + 2., top, MARKER_COLOR);
+        }
+        let stats = match fps {
+            Some(fps) => format!("{fps:.0} FPS {:.1} MS", 1000. / fps),
+            None => "-- FPS".to_string(),
+        };
+        let x = camera.view_size().0 - MARGIN - text_width(&stats, TEXT_SCALE);
+        self.draw_text(gl, &stats, x, MARGIN + GLYPH_HEIGHT * TEXT_SCALE, TEXT_COLOR);
+    }
+
+    fn draw_text(&mut self, gl: &glow::Context, text: &str, x: f32, y: f32, color: Color) {
+        self.text.set_text(gl, text);
+        self.text.set_origin(gl, x, y);
+        self.text.set_color(gl, color.0, color.1, color.2);
+        self.text_mesh.render(gl);
+    }
+}
@@ -0,0 +1,378 @@
+// fraction of the stick travel that is ignored before a direction is reported
+pub const DEAD_ZONE: f32 = 0.35;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+/// translated controller input, delivered to `InputListener::on_input`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GamepadEvent {
+    Direction(Direction),
+    Start,
+    Select,
+}
+
+/// untranslated input as reported by a device, mirrors linux `js_event`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RawGamepadEvent {
+    Button { number: u8, pressed: bool },
+    Axis { number: u8, value: i16 },
+}
+
+pub trait GamepadBackend {
+    fn poll(&mut self, out: &mut Vec<RawGamepadEvent>);
+}
+
+/// button & axis numbers of a controller, defaults follow the linux xpad driver
+#[derive(Clone, Debug)]
+pub struct GamepadMapping {
+    pub stick_x: u8,
+    pub stick_y: u8,
+    pub dpad_x: u8,
+    pub dpad_y: u8,
+    // some drivers report the d-pad as buttons instead of a hat, order is up, right, down, left
+    pub dpad_buttons: Option<[u8; 4]>,
+    pub start: u8,
+    pub select: u8,
+    pub dead_zone: f32,
+}
+
+impl Default for GamepadMapping {
+    fn default() -> Self {
+        Self {
+            stick_x: 0,
+            stick_y: 1,
+            dpad_x: 6,
+            dpad_y: 7,
+            dpad_buttons: None,
+            start: 7,
+            select: 6,
+            dead_zone: DEAD_ZONE,
+        }
+    }
+}
+
+pub struct Gamepad {
+    backend: Box<dyn GamepadBackend>,
+    mapping: GamepadMapping,
+    stick: (f32, f32),
+    dpad: (f32, f32),
+    last_dir: Option<Direction>,
+}
+
+impl Gamepad {
+    pub fn new(backend: Box<dyn GamepadBackend>, mapping: GamepadMapping) -> Self {
+        Self {
+            backend,
+            mapping,
+            stick: (0., 0.),
+            dpad: (0., 0.),
+            last_dir: None,
+        }
+    }
+
+    /// joystick devices on linux, no-op everywhere else <br/>
+    /// `GL_SNAKE_JOYSTICK_DIR` points the linux backend at another directory, a fifo named
+    /// `js0` in there acts as a virtual device that can be fed raw `js_event`s
+    pub fn system_default() -> Self {
+        #[cfg(target_os = "linux")]
+        let backend: Box<dyn GamepadBackend> = Box::new(linux::JoystickDevBackend::new(
+            std::env::var_os("GL_SNAKE_JOYSTICK_DIR").unwrap_or_else(|| "/dev/input".into()),
+        ));
+        #[cfg(not(target_os = "linux"))]
+        let backend: Box<dyn GamepadBackend> = Box::new(NullGamepadBackend);
+
+        Self::new(backend, GamepadMapping::default())
+    }
+
+    pub fn poll(&mut self) -> Vec<GamepadEvent> {
+        let mut raws = Vec::new();
+        self.backend.poll(&mut raws);
+
+        let mut res = Vec::new();
+        for raw in raws {
+            match raw {
+                RawGamepadEvent::Button { number, pressed: true } if number == self.mapping.start => {
+                    res.push(GamepadEvent::Start);
+                }
+                RawGamepadEvent::Button { number, pressed: true } if number == self.mapping.select => {
+                    res.push(GamepadEvent::Select);
+                }
+                RawGamepadEvent::Button { number, pressed } => {
+                    let Some(buttons) = self.mapping.dpad_buttons else {
+                        continue;
+                    };
+                    let val = if pressed { 1. } else { 0. };
+                    match buttons.iter().position(|b| *b == number) {
+                        Some(0) => self.dpad.1 = -val,
+                        Some(1) => self.dpad.0 = val,
+                        Some(2) => self.dpad.1 = val,
+                        Some(3) => self.dpad.0 = -val,
+                        _ => continue,
+                    }
+                }
+                RawGamepadEvent::Axis { number, value } => {
+                    let value = value as f32 / i16::MAX as f32;
+                    if number == self.mapping.stick_x {
+                        self.stick.0 = value;
+                    } else if number == self.mapping.stick_y {
+                        self.stick.1 = value;
+                    } else if number == self.mapping.dpad_x {
+                        self.dpad.0 = value;
+                    } else if number == self.mapping.dpad_y {
+                        self.dpad.1 = value;
+                    } else {
+                        continue;
+                    }
+                }
+            }
+
+            // d-pad wins over the stick when both are held
+            let dir = stick_direction(self.dpad, self.mapping.dead_zone)
+                .or_else(|| stick_direction(self.stick, self.mapping.dead_zone));
+            if dir != self.last_dir {
+                self.last_dir = dir;
+                if let Some(dir) = dir {
+                    res.push(GamepadEvent::Direction(dir));
+                }
+            }
+        }
+        res
+    }
+}
+
+/// dominant axis of a stick position, `None` while inside the dead zone <br/>
+/// the position is in device space where negative y points up
+pub fn stick_direction((x, y): (f32, f32), dead_zone: f32) -> Option<Direction> {
+    if x.hypot(y) < dead_zone {
+        return None;
+    }
+    if x.abs() > y.abs() {
+        Some(if x > 0. { Direction::Right } else { Direction::Left })
+    } else {
+        Some(if y > 0. { Direction::Down } else { Direction::Up })
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub struct NullGamepadBackend;
+
+#[cfg(not(target_os = "linux"))]
+impl GamepadBackend for NullGamepadBackend {
+    fn poll(&mut self, _out: &mut Vec<RawGamepadEvent>) {}
+}
+
+#[cfg(target_os = "linux")]
+pub mod linux {
+    use std::{
+        collections::HashSet,
+        fs::File,
+        io::Read,
+        path::{Path, PathBuf},
+        sync::{
+            Arc, Mutex,
+            mpsc::{self, Receiver, Sender},
+        },
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use super::{GamepadBackend, RawGamepadEvent};
+
+    const JS_EVENT_BUTTON: u8 = 0x01;
+    const JS_EVENT_AXIS: u8 = 0x02;
+    const JS_EVENT_INIT: u8 = 0x80;
+    const JS_EVENT_INIT_AXIS: u8 = JS_EVENT_AXIS | JS_EVENT_INIT;
+    const MAX_DEVICES: usize = 4;
+    const RESCAN_INTERVAL: Duration = Duration::from_secs(2);
+
+    /// reads `/dev/input/js*` through the kernel joystick api, each device is read on
+    /// its own thread and plugged in devices are picked up every `RESCAN_INTERVAL`
+    pub struct JoystickDevBackend {
+        dir: PathBuf,
+        tx: Sender<RawGamepadEvent>,
+        rx: Receiver<RawGamepadEvent>,
+        opened: Arc<Mutex<HashSet<PathBuf>>>,
+        last_scan: Option<Instant>,
+    }
+
+    impl JoystickDevBackend {
+        pub fn new(dir: impl AsRef<Path>) -> Self {
+            let (tx, rx) = mpsc::channel();
+            Self {
+                dir: dir.as_ref().to_path_buf(),
+                tx,
+                rx,
+                opened: Arc::new(Mutex::new(HashSet::new())),
+                last_scan: None,
+            }
+        }
+
+        fn scan(&mut self) {
+            self.last_scan = Some(Instant::now());
+            for i in 0..MAX_DEVICES {
+                let path = self.dir.join(format!("js{i}"));
+                if !path.exists() || !self.opened.lock().unwrap().insert(path.clone()) {
+                    continue;
+                }
+
+                let tx = self.tx.clone();
+                let opened = self.opened.clone();
+                // opening a fifo blocks until it has a writer, so it is opened on the reader thread
+                thread::spawn(move || {
+                    if let Ok(file) = File::open(&path) {
+                        read_device(file, tx);
+                    }
+                    // device unplugged, let the next scan reopen it
+                    opened.lock().unwrap().remove(&path);
+                });
+            }
+        }
+    }
+
+    impl GamepadBackend for JoystickDevBackend {
+        fn poll(&mut self, out: &mut Vec<RawGamepadEvent>) {
+            if self.last_scan.is_none_or(|t| t.elapsed() > RESCAN_INTERVAL) {
+                self.scan();
+            }
+            out.extend(self.rx.try_iter());
+        }
+    }
+
+    fn read_device(mut file: File, tx: Sender<RawGamepadEvent>) {
+        // struct js_event { u32 time; i16 value; u8 type; u8 number; }
+        let mut buf = [0u8; 8];
+        while file.read_exact(&mut buf).is_ok() {
+            if let Some(event) = parse_js_event(&buf)
+                && tx.send(event).is_err()
+            {
+                return;
+            }
+        }
+    }
+
+    fn parse_js_event(buf: &[u8; 8]) -> Option<RawGamepadEvent> {
+        let value = i16::from_ne_bytes([buf[4], buf[5]]);
+        let kind = buf[6];
+        let number = buf[7];
+
+        match kind {
+            JS_EVENT_BUTTON => Some(RawGamepadEvent::Button { number, pressed: value != 0 }),
+            // initial axis state is still useful, initial button state would fire actions on connect
+            JS_EVENT_AXIS | JS_EVENT_INIT_AXIS => {
+                Some(RawGamepadEvent::Axis { number, value })
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+
+    const START: u8 = 7;
+    const SELECT: u8 = 6;
+    const STICK_X: u8 = 0;
+    const STICK_Y: u8 = 1;
+    const DPAD_X: u8 = 6;
+
+    // in memory device, the test queues raw events that the next poll hands out
+    #[derive(Clone, Default)]
+    struct MockBackend(Rc<RefCell<Vec<RawGamepadEvent>>>);
+
+    impl MockBackend {
+        fn push(&self, events: &[RawGamepadEvent]) {
+            self.0.borrow_mut().extend_from_slice(events);
+        }
+    }
+
+    impl GamepadBackend for MockBackend {
+        fn poll(&mut self, out: &mut Vec<RawGamepadEvent>) {
+            out.append(&mut self.0.borrow_mut());
+        }
+    }
+
+    fn gamepad() -> (Gamepad, MockBackend) {
+        let backend = MockBackend::default();
+        (Gamepad::new(Box::new(backend.clone()), GamepadMapping::default()), backend)
+    }
+
+    fn axis(number: u8, value: f32) -> RawGamepadEvent {
+        RawGamepadEvent::Axis { number, value: (value * i16::MAX as f32) as i16 }
+    }
+
+    fn press(number: u8) -> RawGamepadEvent {
+        RawGamepadEvent::Button { number, pressed: true }
+    }
+
+    #[test]
+    fn stick_inside_the_dead_zone_is_ignored() {
+        let (mut pad, device) = gamepad();
+        device.push(&[axis(STICK_X, DEAD_ZONE * 0.9)]);
+        assert!(pad.poll().is_empty());
+
+        device.push(&[axis(STICK_X, DEAD_ZONE * 1.1)]);
+        assert_eq!(pad.poll(), [GamepadEvent::Direction(Direction::Right)]);
+    }
+
+    #[test]
+    fn stick_direction_follows_the_dominant_axis() {
+        assert_eq!(stick_direction((0.2, 0.1), DEAD_ZONE), None);
+        assert_eq!(stick_direction((0.8, -0.5), DEAD_ZONE), Some(Direction::Right));
+        assert_eq!(stick_direction((-0.3, -0.9), DEAD_ZONE), Some(Direction::Up));
+        assert_eq!(stick_direction((0.1, 0.6), DEAD_ZONE), Some(Direction::Down));
+    }
+
+    #[test]
+    fn dpad_wins_over_the_stick() {
+        let (mut pad, device) = gamepad();
+        device.push(&[axis(STICK_Y, -1.)]);
+        assert_eq!(pad.poll(), [GamepadEvent::Direction(Direction::Up)]);
+
+        device.push(&[axis(DPAD_X, -1.)]);
+        assert_eq!(pad.poll(), [GamepadEvent::Direction(Direction::Left)]);
+
+        // letting go of the d-pad falls back to the stick still held
+        device.push(&[axis(DPAD_X, 0.)]);
+        assert_eq!(pad.poll(), [GamepadEvent::Direction(Direction::Up)]);
+    }
+
+    #[test]
+    fn dpad_buttons_when_mapped() {
+        let backend = MockBackend::default();
+        let mapping = GamepadMapping { dpad_buttons: Some([11, 12, 13, 14]), ..GamepadMapping::default() };
+        let mut pad = Gamepad::new(Box::new(backend.clone()), mapping);
+        backend.push(&[press(13)]);
+        assert_eq!(pad.poll(), [GamepadEvent::Direction(Direction::Down)]);
+    }
+
+    // the app pauses on start & restarts on select
+    #[test]
+    fn start_and_select_presses_come_through() {
+        let (mut pad, device) = gamepad();
+        device.push(&[press(START), RawGamepadEvent::Button { number: START, pressed: false }, press(SELECT)]);
+        assert_eq!(pad.poll(), [GamepadEvent::Start, GamepadEvent::Select]);
+    }
+
+    #[test]
+    fn held_direction_is_reported_once() {
+        let (mut pad, device) = gamepad();
+        device.push(&[axis(STICK_X, 0.6), axis(STICK_X, 0.8), axis(STICK_X, 1.), axis(STICK_Y, 0.2)]);
+        assert_eq!(pad.poll(), [GamepadEvent::Direction(Direction::Right)]);
+
+        device.push(&[axis(STICK_X, 0.9)]);
+        assert!(pad.poll().is_empty());
+
+        // back to the center & out again counts as a new direction
+        device.push(&[axis(STICK_X, 0.), axis(STICK_Y, 0.), axis(STICK_X, 1.)]);
+        assert_eq!(pad.poll(), [GamepadEvent::Direction(Direction::Right)]);
+    }
+}
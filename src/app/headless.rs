@@ -0,0 +1,77 @@
+use crate::{app::{App, AppConfig}, error::Error};
+
+/// the app drawing into an offscreen surface, no window or event loop <br/>
+/// frames are only rendered when asked, see `Headless::render`
+pub struct Headless {
+    // dropped before the context it was created with
+    pub app: App,
+    #[cfg(target_os = "linux")]
+    _gl: egl::OffscreenGl,
+}
+
+impl Headless {
+    /// offscreen rendering goes through EGL, only on linux for now
+    pub fn new(on_app_init: fn(&mut App) -> Result<(), Error>, config: AppConfig) -> Result<Self, Error> {
+        #[cfg(target_os = "linux")]
+        {
+            let (gl, offscreen) = egl::OffscreenGl::new(config.width.into(), config.height.into()).map_err(Error::Offscreen)?;
+            let app = App::new(gl, on_app_init, config)?;
+            Ok(Self { app, _gl: offscreen })
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = (on_app_init, config);
+            Err(Error::Offscreen("only supported on linux for now".to_string()))
+        }
+    }
+
+    pub fn render(&mut self) -> Result<(), Error> {
+        self.app.render()
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod egl {
+    use std::num::NonZero;
+
+    use glutin::{
+        api::egl::{context::PossiblyCurrentContext, device::Device, display::Display, surface::Surface},
+        config::{ConfigSurfaceTypes, ConfigTemplateBuilder},
+        context::{ContextApi, ContextAttributesBuilder, Version},
+        prelude::*,
+        surface::{PbufferSurface, SurfaceAttributesBuilder},
+    };
+
+    /// a pbuffer surface with its context made current, picked from the first EGL device
+    pub struct OffscreenGl {
+        _context: PossiblyCurrentContext,
+        _surface: Surface<PbufferSurface>,
+    }
+
+    impl OffscreenGl {
+        pub fn new(width: u32, height: u32) -> Result<(glow::Context, Self), String> {
+            let err = |what: &'static str| move |err: glutin::error::Error| format!("{what} : {err}");
+            let device = Device::query_devices()
+                .map_err(err("can't list the EGL devices"))?
+                .next()
+                .ok_or("no EGL device found")?;
+            let display = unsafe { Display::with_device(&device, None) }.map_err(err("can't open the EGL display"))?;
+            let template = ConfigTemplateBuilder::default().with_surface_type(ConfigSurfaceTypes::PBUFFER).build();
+            let config = unsafe { display.find_configs(template) }
+                .map_err(err("can't list the EGL configs"))?
+                .next()
+                .ok_or("no EGL config supports offscreen surfaces")?;
+            let attrs = ContextAttributesBuilder::new()
+                .with_context_api(ContextApi::OpenGl(Some(Version::new(3, 3))))
+                .build(None);
+            let context = unsafe { display.create_context(&config, &attrs) }.map_err(err("can't create the GL context"))?;
+            let (width, height) = NonZero::new(width).zip(NonZero::new(height)).ok_or("the board can't be empty")?;
+            let surface_attrs = SurfaceAttributesBuilder::<PbufferSurface>::new().build(width, height);
+            let surface = unsafe { display.create_pbuffer_surface(&config, &surface_attrs) }
+                .map_err(err("can't create the offscreen surface"))?;
+            let context = context.make_current(&surface).map_err(err("can't use the GL context"))?;
+            let gl = unsafe { glow::Context::from_loader_function_cstr(|s| display.get_proc_address(s)) };
+            Ok((gl, Self { _context: context, _surface: surface }))
+        }
+    }
+}
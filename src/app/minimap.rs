@@ -0,0 +1,67 @@
+use std::rc::Rc;
+
+use glow::HasContext;
+
+use crate::{
+    app::{board::Board, camera::Camera, theme::{Color, Theme}},
+    error::Error,
+    meshes::InstancedRect,
+    shaders::{DebugShader, Shader},
+};
+
+// same layout as the debug overlay, the part in view is outlined like a collider box
+const INSTANCE_LAYOUT: [(&str, i32); 4] = [("aStart", 2), ("aEnd", 2), ("aRadius", 1), ("aColor", 3)];
+// of the smaller side of the window
+const SIZE: f32 = 0.3;
+const MARGIN: f32 = 8.;
+// leaves room for the top line of the hud
+const TOP: f32 = 40.;
+
+/// the whole board in the top right corner while the view only shows part of it <br/>
+/// the game objects draw themselves again through `Updateable::on_minimap`, between `begin` & `finish`
+pub struct Minimap {
+    shader: DebugShader,
+    mesh: InstancedRect,
+}
+
+impl Minimap {
+    pub fn new(gl: &Rc<glow::Context>) -> Result<Self, Error> {
+        let shader = DebugShader::new(gl)?;
+        let mesh = InstancedRect::new(gl, &shader, &INSTANCE_LAYOUT)?;
+        Ok(Self { shader, mesh })
+    }
+
+    /// looks at the whole board from the corner of a `view` sized window
+    pub fn camera(view: (f32, f32), board: &Board) -> Camera {
+        let zoom = view.0.min(view.1) * SIZE / board.width.max(board.height);
+        let (width, height) = (board.width * zoom, board.height * zoom);
+        Camera::overview((view.0 - MARGIN - width, view.1 - TOP - height, width, height), board)
+    }
+
+    /// framed & cleared to the background, drawing stays inside until `finish`
+    pub fn begin(&self, gl: &glow::Context, camera: &Camera, theme: &Theme) {
+        let (x, y, w, h) = camera.viewport();
+        let (x, y, w, h) = (x as i32, y as i32, w as i32, h as i32);
+        unsafe {
+            gl.enable(glow::SCISSOR_TEST);
+            for ((x, y, w, h), (r, g, b)) in [((x - 1, y - 1, w + 2, h + 2), theme.grid_line), ((x, y, w, h), theme.background)] {
+                gl.scissor(x, y, w, h);
+                gl.clear_color(r, g, b, 1.);
+                gl.clear(glow::COLOR_BUFFER_BIT);
+            }
+            gl.viewport(x, y, w, h);
+        }
+    }
+
+    /// outlines what `view` shows & goes back to drawing on the whole window
+    pub fn finish(&self, gl: &glow::Context, view: &Camera, board: &Board, (r, g, b): Color) {
+        let (min, max) = view.visible();
+        self.shader.use_shader(gl);
+        self.mesh.render(gl, &[min.x.max(0.), min.y.max(0.), max.x.min(board.width), max.y.min(board.height), -1., r, g, b]);
+        let (x, y, w, h) = view.viewport();
+        unsafe {
+            gl.disable(glow::SCISSOR_TEST);
+            gl.viewport(x as i32, y as i32, w as i32, h as i32);
+        }
+    }
+}
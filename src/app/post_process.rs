@@ -0,0 +1,223 @@
+use std::{rc::Rc, time::Duration};
+
+use glow::{HasContext, NativeFramebuffer, NativeTexture};
+use rand::Rng;
+
+use crate::{error::Error, gl_object::GlObject, meshes::UnitRect, shaders::{PostShader, Shader}};
+
+// trauma lost per second
+const SHAKE_DECAY: f32 = 1.5;
+// offset at full trauma, in pixels
+const MAX_SHAKE: f32 = 10.;
+
+/// full screen pass applied to the scene, run in the order of `PostPass::ALL`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PostPass {
+    /// glow around the brightest colors
+    Bloom,
+    /// curved screen, scanlines & color fringing
+    Crt,
+    /// darker corners
+    Vignette,
+}
+
+impl PostPass {
+    pub const ALL: [PostPass; 3] = [PostPass::Bloom, PostPass::Crt, PostPass::Vignette];
+}
+
+/// screen shake fed by gameplay events, eg dying
+#[derive(Default)]
+pub struct Shake {
+    trauma: f32,
+}
+
+impl Shake {
+    /// `amount` from 0 to 1, adds up with the shake already going on
+    pub fn add(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).min(1.);
+    }
+    fn offset(&mut self, delta: Duration) -> (f32, f32) {
+        self.trauma = (self.trauma - SHAKE_DECAY * delta.as_secs_f32()).max(0.);
+        if self.trauma == 0. {
+            return (0., 0.);
+        }
+        // squared so a small trauma stays subtle
+        let strength = self.trauma * self.trauma * MAX_SHAKE;
+        let mut rng = rand::rng();
+        (
+            rng.random_range(-1. ..=1.) * strength,
+            rng.random_range(-1. ..=1.) * strength,
+        )
+    }
+}
+
+struct RenderTarget {
+    framebuffer: GlObject<NativeFramebuffer>,
+    texture: GlObject<NativeTexture>,
+    width: i32,
+    height: i32,
+}
+
+impl RenderTarget {
+    fn new(gl: &Rc<glow::Context>, width: i32, height: i32) -> Result<Self, Error> {
+        unsafe {
+            let texture = GlObject::create(gl, |gl| gl.create_texture())?;
+            gl.bind_texture(glow::TEXTURE_2D, Some(*texture));
+            gl.tex_image_2d(
+                glow::TEXTURE_2D,
+                0,
+                glow::RGBA8 as i32,
+                width,
+                height,
+                0,
+                glow::RGBA,
+                glow::UNSIGNED_BYTE,
+                glow::PixelUnpackData::Slice(None),
+            );
+            gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, glow::LINEAR as i32);
+            gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, glow::LINEAR as i32);
+            gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_S, glow::CLAMP_TO_EDGE as i32);
+            gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE as i32);
+            gl.bind_texture(glow::TEXTURE_2D, None);
+
+            let framebuffer = GlObject::create(gl, |gl| gl.create_framebuffer())?;
+            gl.bind_framebuffer(glow::FRAMEBUFFER, Some(*framebuffer));
+            gl.framebuffer_texture_2d(glow::FRAMEBUFFER, glow::COLOR_ATTACHMENT0, glow::TEXTURE_2D, Some(*texture), 0);
+            let status = gl.check_framebuffer_status(glow::FRAMEBUFFER);
+            gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+            if status != glow::FRAMEBUFFER_COMPLETE {
+                return Err(Error::Gl("the offscreen framebuffer is incomplete", format!("status {status:#x}")));
+            }
+
+            Ok(Self { framebuffer, texture, width, height })
+        }
+    }
+    fn bind_texture(&self, gl: &glow::Context, unit: u32) {
+        unsafe {
+            gl.active_texture(glow::TEXTURE0 + unit);
+            gl.bind_texture(glow::TEXTURE_2D, Some(*self.texture));
+        }
+    }
+}
+
+/// the scene is drawn offscreen, then goes through the enabled passes before reaching the window
+pub struct PostProcess {
+    mesh: UnitRect,
+    // full size, ping ponged between passes
+    targets: [RenderTarget; 2],
+    // half size, for the bloom highlights
+    bloom_targets: [RenderTarget; 2],
+    current: usize,
+    width: i32,
+    height: i32,
+    bright: PostShader,
+    blur: PostShader,
+    bloom: PostShader,
+    crt: PostShader,
+    vignette: PostShader,
+    present: PostShader,
+}
+
+impl PostProcess {
+    pub fn new(gl: &Rc<glow::Context>, width: i32, height: i32) -> Result<Self, Error> {
+        let present = PostShader::new(gl, "present")?;
+        let (targets, bloom_targets) = render_targets(gl, width, height)?;
+        Ok(Self {
+            mesh: UnitRect::new(gl, &present)?,
+            targets,
+            bloom_targets,
+            current: 0,
+            width,
+            height,
+            bright: PostShader::new(gl, "bright")?,
+            blur: PostShader::new(gl, "blur")?,
+            bloom: PostShader::new(gl, "bloom")?,
+            crt: PostShader::new(gl, "crt")?,
+            vignette: PostShader::new(gl, "vignette")?,
+            present,
+        })
+    }
+
+    /// offscreen targets made again at the new window size
+    pub fn resize(&mut self, gl: &Rc<glow::Context>, width: i32, height: i32) -> Result<(), Error> {
+        (self.targets, self.bloom_targets) = render_targets(gl, width, height)?;
+        (self.width, self.height) = (width, height);
+        Ok(())
+    }
+
+    /// everything drawn afterward ends up offscreen
+    pub fn begin(&mut self, gl: &glow::Context) {
+        self.current = 0;
+        bind_target(gl, Some(&self.targets[0]), self.width, self.height);
+    }
+
+    /// run `passes` over the scene & draw the result to the window, moved by the screen shake
+    pub fn finish(&mut self, gl: &glow::Context, passes: &[PostPass], shake: &mut Shake, delta: Duration, elapsed: f32) {
+        let Self { mesh, targets, bloom_targets, current, width, height, bright, blur, bloom, crt, vignette, present } = self;
+        for pass in PostPass::ALL.iter().filter(|pass| passes.contains(pass)) {
+            let src = &targets[*current];
+            let dst = &targets[1 - *current];
+            match pass {
+                PostPass::Bloom => {
+                    let [highlights, blurred] = &*bloom_targets;
+                    draw_pass(gl, mesh, bright, src, Some(highlights));
+                    blur.use_shader(gl);
+                    blur.set_direction(gl, 1. / highlights.width as f32, 0.);
+                    draw_pass(gl, mesh, blur, highlights, Some(blurred));
+                    blur.set_direction(gl, 0., 1. / highlights.height as f32);
+                    draw_pass(gl, mesh, blur, blurred, Some(highlights));
+
+                    highlights.bind_texture(gl, PostShader::BLOOM_UNIT);
+                    draw_pass(gl, mesh, bloom, src, Some(dst));
+                }
+                PostPass::Crt => {
+                    crt.use_shader(gl);
+                    crt.set_time(gl, elapsed);
+                    draw_pass(gl, mesh, crt, src, Some(dst));
+                }
+                PostPass::Vignette => draw_pass(gl, mesh, vignette, src, Some(dst)),
+            }
+            *current = 1 - *current;
+        }
+
+        let (x, y) = shake.offset(delta);
+        present.use_shader(gl);
+        present.set_offset(gl, x, y);
+        bind_target(gl, None, *width, *height);
+        draw_pass(gl, mesh, present, &targets[*current], None);
+        unsafe {
+            gl.active_texture(glow::TEXTURE0);
+        }
+    }
+}
+
+// full size ones, then the half size ones for the bloom
+fn render_targets(gl: &Rc<glow::Context>, width: i32, height: i32) -> Result<([RenderTarget; 2], [RenderTarget; 2]), Error> {
+    Ok((
+        [RenderTarget::new(gl, width, height)?, RenderTarget::new(gl, width, height)?],
+        [RenderTarget::new(gl, width / 2, height / 2)?, RenderTarget::new(gl, width / 2, height / 2)?],
+    ))
+}
+
+fn bind_target(gl: &glow::Context, target: Option<&RenderTarget>, width: i32, height: i32) {
+    unsafe {
+        gl.bind_framebuffer(glow::FRAMEBUFFER, target.map(|t| *t.framebuffer));
+        match target {
+            Some(target) => gl.viewport(0, 0, target.width, target.height),
+            None => gl.viewport(0, 0, width, height),
+        }
+    }
+}
+
+// `output` of None is the window, which is left bound by the caller
+fn draw_pass(gl: &glow::Context, mesh: &mut UnitRect, shader: &PostShader, input: &RenderTarget, output: Option<&RenderTarget>) {
+    shader.use_shader(gl);
+    if let Some(output) = output {
+        bind_target(gl, Some(output), output.width, output.height);
+        shader.set_resolution(gl, output.width as f32, output.height as f32);
+    } else {
+        shader.set_resolution(gl, input.width as f32, input.height as f32);
+    }
+    input.bind_texture(gl, PostShader::SCENE_UNIT);
+    mesh.render(gl);
+}
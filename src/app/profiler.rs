@@ -0,0 +1,146 @@
+use std::{collections::VecDeque, rc::Rc, time::{Duration, Instant}};
+
+use glow::{HasContext, NativeQuery};
+
+use crate::gl_object::GlObject;
+
+/// time spent on each part of a single frame
+#[derive(Clone, Copy, Default)]
+pub struct FrameTimes {
+    /// from the start of the frame until it is handed to the gpu, everything included
+    pub cpu: Duration,
+    /// ticking the game objects, their draw calls included
+    pub sim: Duration,
+    pub collision: Duration,
+    /// None without timer queries, or while the result is still pending
+    pub gpu: Option<Duration>,
+}
+
+/// measures every frame while enabled, see `AppConfig::profile` <br/>
+/// gpu time comes from GL timer queries, read back a few frames later so the cpu never waits on them
+pub struct Profiler {
+    frames: Vec<FrameTimes>,
+    current: FrameTimes,
+    frame_start: Option<Instant>,
+    phase_start: Option<Instant>,
+    gpu: Option<GpuTimer>,
+}
+
+impl Profiler {
+    pub fn new(gl: &glow::Context) -> Self {
+        Self {
+            frames: Vec::new(),
+            current: FrameTimes::default(),
+            frame_start: None,
+            phase_start: None,
+            gpu: GpuTimer::new(gl),
+        }
+    }
+
+    pub fn has_gpu_timer(&self) -> bool {
+        self.gpu.is_some()
+    }
+
+    pub fn begin_frame(&mut self, gl: &Rc<glow::Context>) {
+        self.current = FrameTimes::default();
+        self.frame_start = Some(Instant::now());
+        if let Some(gpu) = self.gpu.as_mut() {
+            gpu.begin(gl, self.frames.len());
+        }
+    }
+
+    pub fn begin_phase(&mut self) {
+        self.phase_start = Some(Instant::now());
+    }
+
+    // time since `begin_phase`
+    fn phase(&mut self) -> Duration {
+        self.phase_start.take().map(|start| start.elapsed()).unwrap_or_default()
+    }
+
+    pub fn end_sim(&mut self) {
+        self.current.sim = self.phase();
+    }
+
+    pub fn end_collision(&mut self) {
+        self.current.collision = self.phase();
+    }
+
+    pub fn end_frame(&mut self, gl: &glow::Context) {
+        let Some(start) = self.frame_start.take() else {
+            return;
+        };
+        self.current.cpu = start.elapsed();
+        self.frames.push(self.current);
+        if let Some(gpu) = self.gpu.as_mut() {
+            gpu.end(gl);
+            gpu.collect(gl, &mut self.frames, false);
+        }
+    }
+
+    /// every frame measured so far, waits for the pending gpu results
+    pub fn finish(&mut self, gl: &glow::Context) -> Vec<FrameTimes> {
+        if let Some(gpu) = self.gpu.as_mut() {
+            gpu.collect(gl, &mut self.frames, true);
+        }
+        std::mem::take(&mut self.frames)
+    }
+}
+
+// one query per frame in flight, recycled once read back
+struct GpuTimer {
+    free: Vec<GlObject<NativeQuery>>,
+    // query & the frame it measures
+    pending: VecDeque<(GlObject<NativeQuery>, usize)>,
+    running: bool,
+}
+
+impl GpuTimer {
+    // GL 3.3 has them built in, older or embedded contexts need the extension
+    fn new(gl: &glow::Context) -> Option<Self> {
+        let version = gl.version();
+        let core = !version.is_embedded && (version.major, version.minor) >= (3, 3);
+        if !core && !gl.supported_extensions().contains("GL_ARB_timer_query") {
+            return None;
+        }
+        Some(Self { free: Vec::new(), pending: VecDeque::new(), running: false })
+    }
+
+    fn begin(&mut self, gl: &Rc<glow::Context>, frame: usize) {
+        let query = match self.free.pop() {
+            Some(query) => query,
+            None => match GlObject::create(gl, |gl| unsafe { gl.create_query() }) {
+                Ok(query) => query,
+                Err(_) => return,
+            },
+        };
+        unsafe { gl.begin_query(glow::TIME_ELAPSED, *query) };
+        self.pending.push_back((query, frame));
+        self.running = true;
+    }
+
+    fn end(&mut self, gl: &glow::Context) {
+        if self.running {
+            unsafe { gl.end_query(glow::TIME_ELAPSED) };
+            self.running = false;
+        }
+    }
+
+    // results come back in order, `wait` blocks until every pending one is there
+    fn collect(&mut self, gl: &glow::Context, frames: &mut [FrameTimes], wait: bool) {
+        while let Some((query, frame)) = self.pending.front() {
+            let available = unsafe { gl.get_query_parameter_u32(**query, glow::QUERY_RESULT_AVAILABLE) } != 0;
+            if !available && !wait {
+                break;
+            }
+            // blocks until the result is there
+            let nanos = unsafe { gl.get_query_parameter_u32(**query, glow::QUERY_RESULT) };
+            if let Some(times) = frames.get_mut(*frame) {
+                times.gpu = Some(Duration::from_nanos(nanos.into()));
+            }
+            if let Some((query, _)) = self.pending.pop_front() {
+                self.free.push(query);
+            }
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use std::time::Duration;
+
+use crate::{app::gamepad::Direction, objects::{bot::BotStrategy, game_mode::GameMode}, records::{self, Records}};
+
+// first line of every recording, bumped whenever the format changes
+const HEADER: &str = "snek-replay 1";
+
+/// turns dispatched since the previous frame & how long the frame took
+#[derive(Default)]
+pub struct RecordedFrame {
+    pub delta: Duration,
+    pub turns: Vec<Direction>,
+}
+
+/// a run kept frame by frame, feeding back the same frame times & turns from the same seed plays it out again
+pub struct Recording {
+    pub width: u16,
+    pub height: u16,
+    pub grid_size: u16,
+    /// columns & rows when the board didn't just fit the window
+    pub cells: Option<(u16, u16)>,
+    pub mode: GameMode,
+    pub seed: u64,
+    pub speed: f32,
+    /// a bot run is played back by the same bot, its turns aren't recorded
+    pub bot: Option<BotStrategy>,
+    pub frames: Vec<RecordedFrame>,
+}
+
+impl Recording {
+    /// see `Records` for the format, frame times in nanoseconds
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![format!("board {} {} {}", self.width, self.height, self.grid_size)];
+        if let Some((cols, rows)) = self.cells {
+            lines.push(format!("cells {cols} {rows}"));
+        }
+        lines.push(format!("mode {}", records::index(&GameMode::ALL, &self.mode)));
+        lines.push(format!("seed {}", self.seed));
+        lines.push(format!("speed {}", self.speed));
+        if let Some(bot) = self.bot {
+            lines.push(format!("bot {}", bot.name()));
+        }
+        for frame in &self.frames {
+            let mut line = format!("frame {}", frame.delta.as_nanos());
+            for turn in &frame.turns {
+                line.push(' ');
+                line.push(match turn {
+                    Direction::Up => 'u',
+                    Direction::Right => 'r',
+                    Direction::Down => 'd',
+                    Direction::Left => 'l',
+                });
+            }
+            lines.push(line);
+        }
+        records::to_text(HEADER, lines)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut rec = Recording {
+            width: 0,
+            height: 0,
+            grid_size: 0,
+            cells: None,
+            mode: GameMode::default(),
+            seed: 0,
+            speed: 1.,
+            bot: None,
+            frames: Vec::new(),
+        };
+        for mut fields in Records::with_header(text, HEADER, "recording")? {
+            let keyword = fields.word()?;
+            match keyword {
+                "board" => {
+                    rec.width = fields.next()?;
+                    rec.height = fields.next()?;
+                    rec.grid_size = fields.next()?;
+                }
+                "cells" => rec.cells = Some((fields.next()?, fields.next()?)),
+                "mode" => rec.mode = fields.pick(&GameMode::ALL)?,
+                "seed" => rec.seed = fields.next()?,
+                "speed" => rec.speed = fields.next()?,
+                "bot" => rec.bot = Some(fields.parse_with(BotStrategy::from_arg)?),
+                "frame" => {
+                    let delta = Duration::from_nanos(fields.next()?);
+                    let turns = fields.rest(|turn| match turn {
+                        "u" => Some(Direction::Up),
+                        "r" => Some(Direction::Right),
+                        "d" => Some(Direction::Down),
+                        "l" => Some(Direction::Left),
+                        _ => None,
+                    })?;
+                    rec.frames.push(RecordedFrame { delta, turns });
+                }
+                _ => return Err(fields.error(format_args!("unknown record `{keyword}`"))),
+            }
+        }
+        if rec.grid_size == 0 {
+            return Err("the board is missing".to_string());
+        }
+        Ok(rec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_keeps_every_frame() {
+        let rec = Recording {
+            width: 800,
+            height: 600,
+            grid_size: 25,
+            cells: Some((40, 30)),
+            mode: GameMode::ALL[1],
+            seed: 42,
+            speed: 1.5,
+            bot: Some(BotStrategy::Random),
+            frames: vec![
+                RecordedFrame { delta: Duration::from_nanos(16_666_667), turns: Vec::new() },
+                RecordedFrame { delta: Duration::from_millis(17), turns: vec![Direction::Up, Direction::Left] },
+            ],
+        };
+        let text = rec.to_text();
+        let parsed = Recording::parse(&text).expect("a fresh recording parses");
+        assert_eq!(parsed.to_text(), text);
+        assert_eq!(parsed.frames[1].turns, [Direction::Up, Direction::Left]);
+        assert_eq!(parsed.bot, Some(BotStrategy::Random));
+
+        let Err(err) = Recording::parse(&(text + "frame 5 x\n")) else {
+            panic!("a bad turn shouldn't parse");
+        };
+        assert_eq!(err, "line 10: can't read `x`");
+    }
+}
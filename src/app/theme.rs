@@ -0,0 +1,186 @@
+use std::{mem, rc::Rc};
+
+use glow::{HasContext, NativeBuffer};
+
+use crate::{error::Error, gl_object::GlObject};
+
+/// uniform block binding point shared by every shader, see `shader/theme.glsl`
+pub const THEME_BINDING: u32 = 0;
+
+pub type Color = (f32, f32, f32);
+
+/// colors of everything on screen
+pub struct Theme {
+    pub background: Color,
+    pub grid_line: Color,
+    /// the body blends from the head color to the tail color
+    pub snake_head: Color,
+    pub snake_tail: Color,
+    /// by `FoodKind`
+    pub food: [Color; 4],
+    /// what food fades into while pulsing
+    pub food_highlight: Color,
+    /// by `PowerUpKind`
+    pub power_up: [Color; 5],
+    pub obstacle: Color,
+    pub ui_text: Color,
+    /// combo & multiplier
+    pub ui_accent: Color,
+    /// running out of time
+    pub ui_warning: Color,
+}
+
+impl Theme {
+    /// color along the body, `fac` is 1 at the head & 0 at the tail
+    pub fn snake_at(&self, fac: f32) -> Color {
+        let (head, tail) = (self.snake_head, self.snake_tail);
+        (
+            tail.0 + (head.0 - tail.0) * fac,
+            tail.1 + (head.1 - tail.1) * fac,
+            tail.2 + (head.2 - tail.2) * fac,
+        )
+    }
+
+    // laid out like the std140 block, every color padded to a vec4
+    fn to_std140(&self) -> Vec<f32> {
+        let colors = [self.background, self.grid_line, self.snake_head, self.snake_tail]
+            .into_iter()
+            .chain(self.food)
+            .chain([self.food_highlight])
+            .chain(self.power_up)
+            .chain([self.obstacle, self.ui_text, self.ui_accent, self.ui_warning]);
+        colors.flat_map(|(r, g, b)| [r, g, b, 1.]).collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ThemePreset {
+    #[default]
+    Classic,
+    Midnight,
+    /// tells things apart by blue, orange & brightness instead of red & green
+    Deuteranopia,
+    /// avoids relying on reds, which look dark & muddy without red cones
+    Protanopia,
+    HighContrast,
+}
+
+impl ThemePreset {
+    pub const ALL: [ThemePreset; 5] = [
+        ThemePreset::Classic,
+        ThemePreset::Midnight,
+        ThemePreset::Deuteranopia,
+        ThemePreset::Protanopia,
+        ThemePreset::HighContrast,
+    ];
+
+    pub fn next(&self) -> Self {
+        let idx = Self::ALL.iter().position(|p| p == self).unwrap_or_default();
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Classic => "CLASSIC",
+            Self::Midnight => "MIDNIGHT",
+            Self::Deuteranopia => "DEUTERANOPIA",
+            Self::Protanopia => "PROTANOPIA",
+            Self::HighContrast => "HIGH CONTRAST",
+        }
+    }
+    pub fn theme(&self) -> Theme {
+        match self {
+            Self::Classic => Theme {
+                background: (0., 0.5, 0.5),
+                grid_line: (0., 0.42, 0.42),
+                snake_head: (1., 1., 1.),
+                snake_tail: (0., 1., 0.),
+                food: [(0., 1., 0.), (1., 0.8, 0.), (0.6, 0.45, 0.1), (0., 0.8, 1.)],
+                food_highlight: (1., 1., 1.),
+                power_up: [(0.3, 0.5, 1.), (1., 0.45, 0.), (0.85, 0.8, 1.), (1., 0.25, 0.3), (1., 0.75, 0.)],
+                obstacle: (0.55, 0.35, 0.3),
+                ui_text: (1., 1., 1.),
+                ui_accent: (1., 0.8, 0.),
+                ui_warning: (1., 0.3, 0.2),
+            },
+            Self::Midnight => Theme {
+                background: (0.05, 0.06, 0.1),
+                grid_line: (0.1, 0.12, 0.19),
+                snake_head: (1., 0.95, 0.75),
+                snake_tail: (0.85, 0.25, 0.6),
+                food: [(0.3, 0.9, 0.6), (1., 0.8, 0.2), (0.55, 0.4, 0.25), (0.3, 0.7, 1.)],
+                food_highlight: (1., 1., 1.),
+                power_up: [(0.35, 0.5, 1.), (1., 0.5, 0.1), (0.8, 0.75, 1.), (1., 0.3, 0.4), (1., 0.8, 0.2)],
+                obstacle: (0.4, 0.42, 0.5),
+                ui_text: (0.9, 0.92, 1.),
+                ui_accent: (1., 0.8, 0.2),
+                ui_warning: (1., 0.35, 0.35),
+            },
+            // okabe ito palette
+            Self::Deuteranopia => Theme {
+                background: (0.08, 0.1, 0.16),
+                grid_line: (0.14, 0.17, 0.26),
+                snake_head: (1., 1., 1.),
+                snake_tail: (0., 0.45, 0.7),
+                food: [(0.9, 0.6, 0.), (0.95, 0.9, 0.25), (0.8, 0.6, 0.7), (0.35, 0.7, 0.9)],
+                food_highlight: (1., 1., 1.),
+                power_up: [(0.35, 0.7, 0.9), (0.9, 0.6, 0.), (0.8, 0.8, 0.8), (0.8, 0.4, 0.), (0.95, 0.9, 0.25)],
+                obstacle: (0.55, 0.55, 0.55),
+                ui_text: (1., 1., 1.),
+                ui_accent: (0.95, 0.9, 0.25),
+                ui_warning: (0.9, 0.6, 0.),
+            },
+            Self::Protanopia => Theme {
+                background: (0.1, 0.1, 0.13),
+                grid_line: (0.17, 0.17, 0.22),
+                snake_head: (0.95, 0.9, 0.25),
+                snake_tail: (0.1, 0.4, 0.85),
+                food: [(0.35, 0.7, 0.9), (1., 1., 0.6), (0.6, 0.55, 0.45), (0.8, 0.6, 0.7)],
+                food_highlight: (1., 1., 1.),
+                power_up: [(0.35, 0.7, 0.9), (0.95, 0.9, 0.25), (0.8, 0.8, 0.8), (0.9, 0.6, 0.), (1., 1., 0.6)],
+                obstacle: (0.5, 0.5, 0.55),
+                ui_text: (1., 1., 1.),
+                ui_accent: (0.95, 0.9, 0.25),
+                ui_warning: (0.35, 0.7, 0.9),
+            },
+            Self::HighContrast => Theme {
+                background: (0., 0., 0.),
+                grid_line: (0.2, 0.2, 0.2),
+                snake_head: (1., 1., 1.),
+                snake_tail: (0.45, 0.45, 0.45),
+                food: [(1., 1., 0.), (1., 0.6, 0.), (0.6, 0.3, 1.), (0., 1., 1.)],
+                food_highlight: (1., 1., 1.),
+                power_up: [(0., 0.6, 1.), (1., 0.5, 0.), (1., 1., 1.), (1., 0., 0.5), (1., 1., 0.)],
+                obstacle: (0.7, 0.7, 0.7),
+                ui_text: (1., 1., 1.),
+                ui_accent: (1., 1., 0.),
+                ui_warning: (1., 0., 0.5),
+            },
+        }
+    }
+}
+
+/// uniform buffer every shader reads the theme from
+pub struct ThemeBuffer {
+    buffer: GlObject<NativeBuffer>,
+}
+
+impl ThemeBuffer {
+    pub fn new(gl: &Rc<glow::Context>) -> Result<Self, Error> {
+        let buffer = unsafe {
+            let buffer = GlObject::create(gl, |gl| gl.create_buffer())?;
+            gl.bind_buffer_base(glow::UNIFORM_BUFFER, THEME_BINDING, Some(*buffer));
+            buffer
+        };
+        Ok(Self { buffer })
+    }
+    pub fn upload(&self, gl: &glow::Context, theme: &Theme) {
+        let data = theme.to_std140();
+        unsafe {
+            let data = core::slice::from_raw_parts(data.as_ptr() as *const u8, data.len() * mem::size_of::<f32>());
+            gl.bind_buffer(glow::UNIFORM_BUFFER, Some(*self.buffer));
+            gl.buffer_data_u8_slice(glow::UNIFORM_BUFFER, data, glow::DYNAMIC_DRAW);
+            gl.bind_buffer(glow::UNIFORM_BUFFER, None);
+            gl.bind_buffer_base(glow::UNIFORM_BUFFER, THEME_BINDING, Some(*self.buffer));
+        }
+    }
+}
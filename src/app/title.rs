@@ -0,0 +1,55 @@
+use std::rc::Rc;
+
+use crate::{
+    app::theme::{Color, Theme},
+    error::Error,
+    meshes::UnitRect,
+    shaders::{GLYPH_HEIGHT, Shader, TextShader, text_width},
+};
+
+const TITLE_SCALE: f32 = 6.;
+const SCALE: f32 = 3.;
+const LINE_HEIGHT: f32 = (GLYPH_HEIGHT + 3.) * SCALE;
+
+/// shown at launch, starts a new run or picks up the saved one
+pub struct Title {
+    text: TextShader,
+    mesh: UnitRect,
+    /// a saved run is waiting
+    pub can_resume: bool,
+}
+
+impl Title {
+    pub fn new(gl: &Rc<glow::Context>, can_resume: bool) -> Result<Self, Error> {
+        let text = TextShader::new(gl)?;
+        let mesh = UnitRect::new(gl, &text)?;
+        Ok(Self { text, mesh, can_resume })
+    }
+
+    /// `mode` is the name of the mode a new run starts in, `view` the window size
+    pub fn render(&mut self, gl: &glow::Context, view: (f32, f32), theme: &Theme, mode: &str) {
+        self.text.use_shader(gl);
+        let top = view.1 * 0.7;
+        self.draw(gl, view.0, "SNEK", top, TITLE_SCALE, theme.ui_accent);
+
+        let mut lines = vec![format!("ENTER  PLAY {mode}")];
+        if self.can_resume {
+            lines.push("C  CONTINUE".to_string());
+        }
+        lines.push("M  CHANGE MODE".to_string());
+        let mut y = top - (GLYPH_HEIGHT * TITLE_SCALE) - LINE_HEIGHT * 2.;
+        for line in lines {
+            self.draw(gl, view.0, &line, y, SCALE, theme.ui_text);
+            y -= LINE_HEIGHT;
+        }
+    }
+
+    // centered horizontally, `y` is the top of the text
+    fn draw(&mut self, gl: &glow::Context, width: f32, text: &str, y: f32, scale: f32, (r, g, b): Color) {
+        self.text.set_scale(gl, scale);
+        self.text.set_text(gl, text);
+        self.text.set_origin(gl, (width - text_width(text, scale)) / 2., y);
+        self.text.set_color(gl, r, g, b);
+        self.mesh.render(gl);
+    }
+}
@@ -0,0 +1,219 @@
+use std::{collections::HashMap, path::PathBuf, rc::Rc, time::Duration};
+
+use crate::log;
+
+mod mixer;
+mod music;
+mod output;
+mod synth;
+
+use mixer::Mixer;
+use music::Sequencer;
+pub use music::MusicCue;
+use output::{DeviceOutput, NullOutput, Output, WavOutput};
+
+/// samples per second, every sound & output is mono at this rate
+pub const SAMPLE_RATE: u32 = 44_100;
+// a longer frame, eg while the window is dragged, only gets that much sound
+// so the device doesn't lag behind for the rest of the session
+const MAX_FRAME: Duration = Duration::from_millis(100);
+// volumes move by that much per key press
+const VOLUME_STEP: f32 = 0.1;
+
+/// short effects synthesized at launch, no sound file needed
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Sfx {
+    /// rising blip
+    Eat,
+    /// soft tick, on every turn taken
+    Turn,
+    /// falling buzz
+    Death,
+    /// three note arpeggio
+    PowerUp,
+}
+
+impl Sfx {
+    pub const ALL: [Sfx; 4] = [Sfx::Eat, Sfx::Turn, Sfx::Death, Sfx::PowerUp];
+}
+
+/// from 0 to 1, the sfx & music ones are scaled by the master one
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Volumes {
+    pub master: f32,
+    pub sfx: f32,
+    pub music: f32,
+    pub muted: bool,
+}
+
+impl Default for Volumes {
+    fn default() -> Self {
+        Self { master: 0.8, sfx: 0.8, music: 0.6, muted: false }
+    }
+}
+
+impl Volumes {
+    /// what the effects get multiplied by
+    pub fn sfx_gain(&self) -> f32 {
+        if self.muted { 0. } else { self.master * self.sfx }
+    }
+    pub fn music_gain(&self) -> f32 {
+        if self.muted { 0. } else { self.master * self.music }
+    }
+    /// one step up or down, stays between 0 & 1
+    pub fn nudge(volume: &mut f32, up: bool) {
+        let step = if up { VOLUME_STEP } else { -VOLUME_STEP };
+        // rounded so steps don't drift away from tenths
+        *volume = ((*volume + step) * 10.).round().clamp(0., 10.) / 10.;
+    }
+}
+
+/// where the mix goes, see `AppConfig::audio`
+#[derive(Clone, Debug, Default)]
+pub enum AudioBackend {
+    /// the sound card through `aplay`, silent when it isn't installed
+    #[default]
+    Device,
+    /// mixed & thrown away
+    Null,
+    /// everything played, written as a 16 bit WAV file on exit
+    Wav(PathBuf),
+}
+
+/// plays the music & the effects asked for by the game objects, mixed a frame at a time <br/>
+/// the mix only moves forward with `advance`, so a fixed frame time always gives the same samples
+pub struct Audio {
+    mixer: Mixer,
+    music: Sequencer,
+    output: Box<dyn Output>,
+    // rendered once, shared by every voice playing them
+    sounds: HashMap<Sfx, Rc<[f32]>>,
+    // fraction of a sample left over from the previous frame
+    carry: f64,
+    buffer: Vec<f32>,
+}
+
+impl Audio {
+    pub fn new(backend: &AudioBackend) -> Self {
+        let output: Box<dyn Output> = match backend {
+            AudioBackend::Device => match DeviceOutput::open() {
+                Ok(output) => Box::new(output),
+                Err(err) => {
+                    log::event!(Info, "audio", "no sound device, playing silently", error = err);
+                    Box::new(NullOutput)
+                }
+            },
+            AudioBackend::Null => Box::new(NullOutput),
+            AudioBackend::Wav(path) => Box::new(WavOutput::new(path.clone())),
+        };
+        let sounds = Sfx::ALL.into_iter().map(|sfx| (sfx, Rc::from(synth::render(sfx)))).collect();
+        Self { mixer: Mixer::default(), music: Sequencer::default(), output, sounds, carry: 0., buffer: Vec::new() }
+    }
+
+    /// starts on the next `advance`, over whatever is already playing
+    pub fn play(&mut self, sfx: Sfx) {
+        log::event!(Trace, "audio", "play", sfx = sfx);
+        self.mixer.play(self.sounds[&sfx].clone());
+    }
+
+    /// the music follows it from the next `advance` on
+    pub fn cue_music(&mut self, cue: MusicCue) {
+        self.music.cue(cue);
+    }
+
+    /// mix `delta` worth of samples & hand them to the output, the music ducks while `paused`
+    pub fn advance(&mut self, delta: Duration, volumes: &Volumes, paused: bool) {
+        let samples = delta.min(MAX_FRAME).as_secs_f64() * SAMPLE_RATE as f64 + self.carry;
+        self.carry = samples.fract();
+        self.buffer.clear();
+        self.buffer.resize(samples as usize, 0.);
+        self.music.set_paused(paused);
+        self.music.render(&mut self.buffer, volumes.music_gain());
+        // clips the music too
+        self.mixer.mix(&mut self.buffer, volumes.sfx_gain());
+        self.output.write(&self.buffer);
+    }
+
+    /// flushes what the output still holds, eg writes the WAV file
+    pub fn finish(&mut self) {
+        self.output.finish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a frame at 60 fps, like the headless runs
+    const FRAME: Duration = Duration::from_micros(16_667);
+
+    // `sfx` alone through the null backend, music off, every frame mixed over `secs`
+    fn render(sfx: Sfx, volumes: Volumes, secs: f32) -> Vec<f32> {
+        let volumes = Volumes { music: 0., ..volumes };
+        let mut audio = Audio::new(&AudioBackend::Null);
+        audio.play(sfx);
+        let mut out = Vec::new();
+        while (out.len() as f32) < secs * SAMPLE_RATE as f32 {
+            audio.advance(FRAME, &volumes, false);
+            out.extend_from_slice(&audio.buffer);
+        }
+        out
+    }
+
+    fn full() -> Volumes {
+        Volumes { master: 1., sfx: 1., music: 1., muted: false }
+    }
+
+    #[test]
+    fn every_effect_plays_for_its_length() {
+        for sfx in Sfx::ALL {
+            let len = synth::render(sfx).len();
+            let out = render(sfx, full(), 1.);
+            assert!(out.len() > len, "{sfx:?} got cut short");
+            assert!(out[..len].iter().any(|sample| sample.abs() > 0.01), "{sfx:?} is silent");
+            assert!(out[len..].iter().all(|sample| *sample == 0.), "{sfx:?} plays past its end");
+        }
+    }
+
+    #[test]
+    fn frames_get_their_share_of_samples() {
+        let mut audio = Audio::new(&AudioBackend::Null);
+        let mut total = 0;
+        for _ in 0..60 {
+            audio.advance(FRAME, &full(), false);
+            total += audio.buffer.len();
+        }
+        // the fraction left over each frame carries to the next one
+        let expected = FRAME.as_secs_f64() * 60. * SAMPLE_RATE as f64;
+        assert!((total as f64 - expected).abs() < 1.);
+    }
+
+    #[test]
+    fn volumes_scale_the_effects() {
+        for sfx in Sfx::ALL {
+            let loud = render(sfx, full(), 1.);
+            for volumes in [Volumes { master: 0.5, ..full() }, Volumes { sfx: 0.5, ..full() }] {
+                let quiet = render(sfx, volumes, 1.);
+                assert_eq!(loud.len(), quiet.len());
+                for (loud, quiet) in loud.iter().zip(&quiet) {
+                    assert!((loud * 0.5 - quiet).abs() < 1e-6, "{sfx:?} isn't halved by {volumes:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn muted_is_silent() {
+        // the music too, every layer of it
+        let volumes = Volumes { muted: true, ..full() };
+        let mut audio = Audio::new(&AudioBackend::Null);
+        audio.cue_music(MusicCue { layers: 4, ..MusicCue::default() });
+        for sfx in Sfx::ALL {
+            audio.play(sfx);
+        }
+        for _ in 0..60 {
+            audio.advance(FRAME, &volumes, false);
+            assert!(audio.buffer.iter().all(|sample| *sample == 0.));
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use std::rc::Rc;
+
+// the oldest sound stops past that many at once
+const MAX_VOICES: usize = 16;
+
+// a sound being played, until its last sample
+struct Voice {
+    samples: Rc<[f32]>,
+    at: usize,
+}
+
+/// adds up every sound playing into a single mono stream
+#[derive(Default)]
+pub struct Mixer {
+    voices: Vec<Voice>,
+}
+
+impl Mixer {
+    pub fn play(&mut self, samples: Rc<[f32]>) {
+        if self.voices.len() >= MAX_VOICES {
+            self.voices.remove(0);
+        }
+        self.voices.push(Voice { samples, at: 0 });
+    }
+
+    /// the next `out.len()` samples of every voice added to `out`, scaled by `gain` & clipped to -1..1 <br/>
+    /// voices that ran out are dropped
+    pub fn mix(&mut self, out: &mut [f32], gain: f32) {
+        for voice in &mut self.voices {
+            let rest = &voice.samples[voice.at..];
+            let len = rest.len().min(out.len());
+            for (out, sample) in out.iter_mut().zip(&rest[..len]) {
+                *out += sample * gain;
+            }
+            voice.at += len;
+        }
+        self.voices.retain(|voice| voice.at < voice.samples.len());
+        for sample in out {
+            *sample = sample.clamp(-1., 1.);
+        }
+    }
+}
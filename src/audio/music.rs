@@ -0,0 +1,270 @@
+use crate::{
+    audio::{
+        SAMPLE_RATE,
+        synth::{Noise, pulse, sine, square, triangle},
+    },
+    log,
+};
+
+// at the normal snek speed, 4 steps a beat
+const BPM: f32 = 110.;
+const STEPS_PER_BAR: usize = 16;
+// root & whether it's minor, a bar each, looped
+const PROGRESSION: [(u8, bool); 4] = [(45, true), (41, false), (48, false), (43, false)];
+// step in the bar & which chord note the lead plays there
+const MELODY: [(usize, usize); 5] = [(0, 2), (3, 1), (6, 0), (10, 1), (12, 2)];
+// bass, drums, arpeggio & lead, in the order they come in
+const LAYERS: usize = 4;
+// a very fast or slow snek doesn't take the music along all the way
+const TEMPO_RANGE: (f32, f32) = (0.5, 2.);
+// music level while paused or once the run is over
+const DUCKED: f32 = 0.25;
+// roughly how long the music takes to duck & come back
+const DUCK_TIME: f32 = 0.2;
+const ATTACK: f32 = 0.003;
+
+// channels, a new note cuts the previous one on the same channel
+const BASS: usize = 0;
+const KICK: usize = 1;
+const NOISE: usize = 2;
+const ARP: usize = 3;
+const LEAD: usize = 4;
+
+/// what the music follows, sent by the snek every tick
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MusicCue {
+    /// relative to the normal speed, kept between half & twice as fast
+    pub tempo: f32,
+    /// layers playing, from 1 to `LAYERS`
+    pub layers: usize,
+    /// the run is over, the music ducks
+    pub over: bool,
+}
+
+impl Default for MusicCue {
+    fn default() -> Self {
+        Self { tempo: 1., layers: 1, over: false }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Wave {
+    Tone(fn(f32) -> f32),
+    Noise,
+}
+
+// a note fading out from the step it started on
+#[derive(Clone, Copy)]
+struct Note {
+    wave: Wave,
+    freq: f32,
+    // extra hertz at the start, gone quickly, makes a kick out of a sine
+    sweep: f32,
+    level: f32,
+    decay: f32,
+    age: f32,
+    phase: f32,
+}
+
+impl Note {
+    fn new(wave: Wave, freq: f32, level: f32, decay: f32) -> Self {
+        Self { wave, freq, sweep: 0., level, decay, age: 0., phase: 0. }
+    }
+
+    fn next(&mut self, noise: &mut Noise) -> f32 {
+        let envelope = self.level * (-self.decay * self.age).exp() * (self.age / ATTACK).min(1.);
+        let sample = match self.wave {
+            Wave::Tone(wave) => wave(self.phase),
+            Wave::Noise => noise.next(),
+        };
+        let freq = self.freq + self.sweep * (-30. * self.age).exp();
+        self.phase = (self.phase + freq / SAMPLE_RATE as f32).fract();
+        self.age += 1. / SAMPLE_RATE as f32;
+        sample * envelope
+    }
+}
+
+fn midi_freq(note: u8) -> f32 {
+    440. * 2f32.powf((note as f32 - 69.) / 12.)
+}
+
+/// looping chiptune made up on the fly from a chord progression <br/>
+/// only moves forward with `render`, so the same cues always give the same samples
+pub struct Sequencer {
+    cue: MusicCue,
+    paused: bool,
+    // steps since the start, the fraction is how far into the current one
+    position: f64,
+    next_step: usize,
+    notes: [Option<Note>; 5],
+    noise: Noise,
+    duck: f32,
+}
+
+impl Default for Sequencer {
+    fn default() -> Self {
+        Self { cue: MusicCue::default(), paused: false, position: 0., next_step: 0, notes: [None; 5], noise: Noise(0x9e37_79b9), duck: 1. }
+    }
+}
+
+impl Sequencer {
+    pub fn cue(&mut self, cue: MusicCue) {
+        if cue.layers.min(LAYERS) != self.cue.layers.min(LAYERS) {
+            log::event!(Debug, "audio", "music layers", layers = cue.layers.min(LAYERS));
+        }
+        self.cue = cue;
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /// the next `out.len()` samples added to `out`, scaled by `gain`
+    pub fn render(&mut self, out: &mut [f32], gain: f32) {
+        let steps_per_sample = self.steps_per_sample();
+        let target = if self.paused || self.cue.over { DUCKED } else { 1. };
+        let duck_rate = 1. / (DUCK_TIME * SAMPLE_RATE as f32);
+        for out in out {
+            if self.position >= self.next_step as f64 {
+                self.trigger(self.next_step);
+                self.next_step += 1;
+            }
+            self.position += steps_per_sample;
+
+            self.duck += (target - self.duck) * duck_rate;
+            let mut sample = 0.;
+            for note in self.notes.iter_mut().flatten() {
+                sample += note.next(&mut self.noise);
+            }
+            *out += sample * self.duck * gain;
+        }
+    }
+
+    fn steps_per_sample(&self) -> f64 {
+        (BPM / 60. * 4. * self.cue.tempo.clamp(TEMPO_RANGE.0, TEMPO_RANGE.1) / SAMPLE_RATE as f32) as f64
+    }
+
+    // start the notes of `step` on the layers playing
+    fn trigger(&mut self, step: usize) {
+        let layers = self.cue.layers.clamp(1, LAYERS);
+        let in_bar = step % STEPS_PER_BAR;
+        let (root, minor) = PROGRESSION[step / STEPS_PER_BAR % PROGRESSION.len()];
+        let chord = [root, root + if minor { 3 } else { 4 }, root + 7];
+
+        // eighths, jumping an octave up on the off beats
+        if in_bar.is_multiple_of(2) {
+            let octave = if in_bar % 4 == 2 { 12 } else { 0 };
+            self.notes[BASS] = Some(Note::new(Wave::Tone(triangle), midi_freq(root + octave), 0.3, 5.));
+        }
+        if layers >= 2 {
+            if in_bar.is_multiple_of(4) {
+                let mut kick = Note::new(Wave::Tone(sine), 50., 0.45, 14.);
+                kick.sweep = 110.;
+                self.notes[KICK] = Some(kick);
+            }
+            match in_bar % 8 {
+                // snare
+                4 => self.notes[NOISE] = Some(Note::new(Wave::Noise, 0., 0.12, 16.)),
+                // hat
+                2 | 6 => self.notes[NOISE] = Some(Note::new(Wave::Noise, 0., 0.05, 60.)),
+                _ => {}
+            }
+        }
+        if layers >= 3 {
+            let note = chord[step % chord.len()] + 24;
+            self.notes[ARP] = Some(Note::new(Wave::Tone(pulse), midi_freq(note), 0.06, 18.));
+        }
+        if layers >= 4
+            && let Some((_, idx)) = MELODY.iter().find(|(at, _)| *at == in_bar)
+        {
+            self.notes[LEAD] = Some(Note::new(Wave::Tone(square), midi_freq(chord[*idx] + 24), 0.07, 3.));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // samples in a 60 fps frame, rounded down
+    const FRAME: usize = SAMPLE_RATE as usize / 60;
+
+    // `secs` of music mixed a frame at a time, like `Audio::advance` does
+    fn render(sequencer: &mut Sequencer, secs: f32, frame: usize) -> Vec<f32> {
+        let mut out = vec![0.; (secs * SAMPLE_RATE as f32) as usize];
+        for chunk in out.chunks_mut(frame) {
+            sequencer.render(chunk, 1.);
+        }
+        out
+    }
+
+    fn cued(cue: MusicCue) -> Sequencer {
+        let mut sequencer = Sequencer::default();
+        sequencer.cue(cue);
+        sequencer
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|sample| sample * sample).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn same_cue_renders_the_same_samples() {
+        let cue = MusicCue { tempo: 1.3, layers: 4, over: false };
+        // frame sizes don't matter either
+        let a = render(&mut cued(cue), 3., FRAME);
+        let b = render(&mut cued(cue), 3., 1000);
+        assert_eq!(a, b);
+        assert!(rms(&a) > 0.01);
+    }
+
+    #[test]
+    fn faster_tempo_shortens_the_step() {
+        let period = |tempo| 1. / cued(MusicCue { tempo, ..MusicCue::default() }).steps_per_sample();
+        assert!(period(1.5) < period(1.));
+        assert!(period(1.) < period(0.75));
+        // a very fast snek doesn't take the music along all the way
+        assert_eq!(period(10.), period(TEMPO_RANGE.1));
+
+        let steps = |tempo| {
+            let mut sequencer = cued(MusicCue { tempo, ..MusicCue::default() });
+            render(&mut sequencer, 2., FRAME);
+            sequencer.next_step
+        };
+        assert!(steps(1.5) > steps(1.));
+    }
+
+    #[test]
+    fn more_layers_add_notes() {
+        let channels = |layers| {
+            let mut sequencer = cued(MusicCue { layers, ..MusicCue::default() });
+            // a whole bar, so every layer got to play
+            render(&mut sequencer, 60. / BPM * 4., FRAME);
+            sequencer.notes.iter().filter(|note| note.is_some()).count()
+        };
+        assert_eq!(channels(1), 1);
+        assert!(channels(2) > channels(1));
+        assert!(channels(3) > channels(2));
+        assert_eq!(channels(LAYERS), 5);
+
+        let loudness = |layers| rms(&render(&mut cued(MusicCue { layers, ..MusicCue::default() }), 2., FRAME));
+        assert!(loudness(4) > loudness(1));
+    }
+
+    #[test]
+    fn pause_and_game_over_duck() {
+        // past the first second the ducking has settled
+        let level = |cue, paused| {
+            let mut sequencer = cued(cue);
+            sequencer.set_paused(paused);
+            rms(&render(&mut sequencer, 3., FRAME)[SAMPLE_RATE as usize..])
+        };
+        let playing = level(MusicCue::default(), false);
+        let paused = level(MusicCue::default(), true);
+        let over = level(MusicCue { over: true, ..MusicCue::default() }, false);
+        assert!(paused < playing * 0.5, "paused {paused} playing {playing}");
+        assert!(over < playing * 0.5, "over {over} playing {playing}");
+        // still heard
+        assert!(paused > 0.);
+    }
+}
@@ -0,0 +1,135 @@
+use std::{
+    fs,
+    io::Write,
+    path::PathBuf,
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::mpsc::{self, Sender},
+    thread::JoinHandle,
+};
+
+use crate::{audio::SAMPLE_RATE, log};
+
+// silence sent ahead of the first frame, so the device doesn't run dry between two frames
+const LEAD_SAMPLES: usize = SAMPLE_RATE as usize / 20;
+
+/// where the mixed samples go, from -1 to 1
+pub trait Output {
+    fn write(&mut self, samples: &[f32]);
+    /// nothing will be written after it
+    fn finish(&mut self) {}
+}
+
+fn to_pcm(sample: f32) -> i16 {
+    (sample * i16::MAX as f32) as i16
+}
+
+/// throws everything away, for headless runs & when there's no sound device
+pub struct NullOutput;
+
+impl Output for NullOutput {
+    fn write(&mut self, _samples: &[f32]) {}
+}
+
+/// keeps everything & writes it as a 16 bit mono WAV file when finished
+pub struct WavOutput {
+    path: PathBuf,
+    samples: Vec<i16>,
+}
+
+impl WavOutput {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path, samples: Vec::new() }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let data_len = self.samples.len() as u32 * 2;
+        let mut bytes = Vec::with_capacity(44 + data_len as usize);
+        bytes.extend(b"RIFF");
+        bytes.extend((36 + data_len).to_le_bytes());
+        bytes.extend(b"WAVEfmt ");
+        // pcm, mono, 2 bytes a sample
+        bytes.extend(16u32.to_le_bytes());
+        bytes.extend(1u16.to_le_bytes());
+        bytes.extend(1u16.to_le_bytes());
+        bytes.extend(SAMPLE_RATE.to_le_bytes());
+        bytes.extend((SAMPLE_RATE * 2).to_le_bytes());
+        bytes.extend(2u16.to_le_bytes());
+        bytes.extend(16u16.to_le_bytes());
+        bytes.extend(b"data");
+        bytes.extend(data_len.to_le_bytes());
+        for sample in &self.samples {
+            bytes.extend(sample.to_le_bytes());
+        }
+        bytes
+    }
+}
+
+impl Output for WavOutput {
+    fn write(&mut self, samples: &[f32]) {
+        self.samples.extend(samples.iter().copied().map(to_pcm));
+    }
+    fn finish(&mut self) {
+        if let Err(err) = fs::write(&self.path, self.to_bytes()) {
+            log::event!(Error, "audio", "can't write the sound", path = self.path, error = err);
+        }
+    }
+}
+
+/// streams to the sound card through `aplay`, fed by a thread so a full pipe never stalls a frame
+pub struct DeviceOutput {
+    // None once finished
+    sender: Option<Sender<Vec<i16>>>,
+    writer: Option<JoinHandle<()>>,
+    child: Child,
+}
+
+impl DeviceOutput {
+    pub fn open() -> Result<Self, String> {
+        let mut child = Command::new("aplay")
+            .args(["-q", "-t", "raw", "-f", "S16_LE", "-c", "1", "-r", &SAMPLE_RATE.to_string(), "--buffer-time=80000", "-"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|err| format!("can't start aplay : {err}"))?;
+        let stdin = child.stdin.take().ok_or("aplay has no input")?;
+        let (sender, receiver) = mpsc::channel();
+        sender.send(vec![0; LEAD_SAMPLES]).ok();
+        let writer = std::thread::spawn(move || write_all(stdin, receiver));
+        Ok(Self { sender: Some(sender), writer: Some(writer), child })
+    }
+}
+
+// until the output is finished or aplay is gone
+fn write_all(mut stdin: ChildStdin, receiver: mpsc::Receiver<Vec<i16>>) {
+    for samples in receiver {
+        let bytes: Vec<u8> = samples.iter().flat_map(|sample| sample.to_le_bytes()).collect();
+        if stdin.write_all(&bytes).is_err() {
+            log::event!(Warn, "audio", "the sound device went away");
+            return;
+        }
+    }
+}
+
+impl Output for DeviceOutput {
+    fn write(&mut self, samples: &[f32]) {
+        if let Some(sender) = &self.sender {
+            // a dead writer already said so
+            sender.send(samples.iter().copied().map(to_pcm).collect()).ok();
+        }
+    }
+    fn finish(&mut self) {
+        // closing the channel ends the writer, which closes aplay's input so it plays what's left & quits
+        self.sender = None;
+        if let Some(writer) = self.writer.take() {
+            writer.join().ok();
+        }
+        self.child.wait().ok();
+    }
+}
+
+impl Drop for DeviceOutput {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
@@ -0,0 +1,87 @@
+use std::f32::consts::TAU;
+
+use crate::audio::{Sfx, SAMPLE_RATE};
+
+// fade in so a sound doesn't start with a click
+const ATTACK: f32 = 0.002;
+// starts of the power up arpeggio, in seconds
+const ARPEGGIO: [(f32, f32); 3] = [(0., 1046.5), (0.07, 1318.5), (0.14, 1568.)];
+
+/// every sample of `sfx`, always the same ones
+pub fn render(sfx: Sfx) -> Vec<f32> {
+    match sfx {
+        Sfx::Eat => tone(0.09, |t| 660. + 660. * t, square, |t| 0.25 * (1. - t) * (1. - t)),
+        Sfx::Turn => tone(0.025, |_| 1800., sine, |t| 0.15 * (-6. * t).exp()),
+        Sfx::Death => {
+            let mut noise = Noise(0x2545_f491);
+            let mut buzz = tone(0.7, |t| 440. * (55f32 / 440.).powf(t), saw, |t| 0.3 * (1. - t));
+            let len = buzz.len() as f32;
+            for (i, sample) in buzz.iter_mut().enumerate() {
+                *sample += noise.next() * 0.12 * (1. - i as f32 / len);
+            }
+            buzz
+        }
+        Sfx::PowerUp => {
+            let note_len = 0.25;
+            let mut out = vec![0.; samples(ARPEGGIO[2].0 + note_len)];
+            for (start, freq) in ARPEGGIO {
+                let note = tone(note_len, |_| freq, sine, |t| 0.2 * (-4. * t).exp());
+                for (out, sample) in out[samples(start)..].iter_mut().zip(note) {
+                    *out += sample;
+                }
+            }
+            out
+        }
+    }
+}
+
+fn samples(secs: f32) -> usize {
+    (secs * SAMPLE_RATE as f32) as usize
+}
+
+/// `secs` of `wave` at `freq`, shaped by `envelope`, both given the time from 0 to 1 <br/>
+/// the phase carries over from sample to sample so frequency sweeps stay smooth
+fn tone(secs: f32, freq: impl Fn(f32) -> f32, wave: fn(f32) -> f32, envelope: impl Fn(f32) -> f32) -> Vec<f32> {
+    let len = samples(secs);
+    let mut phase = 0.;
+    (0..len)
+        .map(|i| {
+            let t = i as f32 / len as f32;
+            let attack = (i as f32 / samples(ATTACK) as f32).min(1.);
+            let sample = wave(phase) * envelope(t) * attack;
+            phase = (phase + freq(t) / SAMPLE_RATE as f32).fract();
+            sample
+        })
+        .collect()
+}
+
+// waves over a phase from 0 to 1, shared with the music
+pub fn sine(phase: f32) -> f32 {
+    (phase * TAU).sin()
+}
+pub fn square(phase: f32) -> f32 {
+    if phase < 0.5 { 1. } else { -1. }
+}
+/// thinner than the square, chiptune leads use it
+pub fn pulse(phase: f32) -> f32 {
+    if phase < 0.25 { 1. } else { -1. }
+}
+pub fn triangle(phase: f32) -> f32 {
+    1. - 4. * (phase - 0.5).abs()
+}
+pub fn saw(phase: f32) -> f32 {
+    2. * phase - 1.
+}
+
+/// xorshift, seeded so every launch makes the same noise
+pub struct Noise(pub u32);
+
+impl Noise {
+    /// from -1 to 1
+    pub fn next(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0 as f32 / u32::MAX as f32 * 2. - 1.
+    }
+}
@@ -0,0 +1,294 @@
+use std::{fs, path::Path, time::{Duration, Instant}};
+
+use glow::HasContext;
+use rand::Rng;
+
+use crate::{
+    app::{board::Position, headless::Headless, profiler::FrameTimes, App, AppConfig},
+    audio::AudioBackend,
+    error::Error,
+    objects::{bot::BotStrategy, game_mode::GameMode, game_rng::GameRng, obstacles::MAX_OBSTACLES, save_game::{SaveGame, SnekSave}, snek::{DirKeypoint, MoveDir}},
+    MAX_FPS,
+};
+
+// always the same runs, so benchmarks can be compared
+const SEED: u64 = 1;
+// shader compilation & first uploads, left out of the statistics
+const WARMUP_FRAMES: usize = 30;
+const LARGE_BOARD: u16 = 800;
+const LARGE_GRID: u16 = 16;
+// the long snek is folded into this many cells wide, on a board tall enough for its rows,
+// an odd number of rows so the last one heads right, out of the fold
+const SERPENTINE_WIDTH: usize = 6;
+const SERPENTINE_ROWS: usize = 63;
+const MANY_FOODS: usize = 60;
+// next to a full board of obstacles
+const COLLIDER_FOODS: usize = 30;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scenario {
+    /// the normal board, like a player would see it
+    Classic,
+    /// a snek folded into 120+ keypoints on a tall board, in zen so it keeps them
+    LongSnek,
+    /// 60 food slots on a large board
+    ManyFoods,
+    /// as many obstacles as the game allows & 30 food slots on a large board, in survival
+    ManyColliders,
+}
+
+impl Scenario {
+    pub const ALL: [Scenario; 4] = [Scenario::Classic, Scenario::LongSnek, Scenario::ManyFoods, Scenario::ManyColliders];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Classic => "classic",
+            Self::LongSnek => "long-snek",
+            Self::ManyFoods => "many-foods",
+            Self::ManyColliders => "many-colliders",
+        }
+    }
+    pub fn from_arg(arg: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.name() == arg)
+    }
+
+    /// a seeded greedy bot at a fixed frame time, only the time taken changes between runs
+    fn config(&self) -> AppConfig {
+        let mut config = AppConfig {
+            skip_title: true,
+            fixed_delta: Some(Duration::from_secs_f32(1. / MAX_FPS)),
+            profile: true,
+            audio: AudioBackend::Null,
+            ..AppConfig::default()
+        };
+        config.settings.seed = Some(SEED);
+        config.settings.bot = Some(BotStrategy::Greedy);
+        config.settings.persist = false;
+        match self {
+            Self::Classic => {}
+            Self::LongSnek => {
+                config.width = LARGE_BOARD;
+                config.height = LARGE_GRID * (SERPENTINE_ROWS as u16 + 8);
+                config.grid_size = LARGE_GRID;
+                config.start_from = Some(long_snek(LARGE_GRID as f32).to_text());
+            }
+            Self::ManyFoods => {
+                config.width = LARGE_BOARD;
+                config.height = LARGE_BOARD;
+                config.grid_size = LARGE_GRID;
+                config.settings.food_count = MANY_FOODS;
+            }
+            Self::ManyColliders => {
+                config.width = LARGE_BOARD;
+                config.height = LARGE_BOARD;
+                config.grid_size = LARGE_GRID;
+                config.settings.food_count = COLLIDER_FOODS;
+                config.start_from = Some(many_obstacles(LARGE_BOARD, LARGE_GRID as f32).to_text());
+            }
+        }
+        config
+    }
+}
+
+/// p50, p95, p99 & max of a single part of the frame
+struct Spread {
+    p50: Duration,
+    p95: Duration,
+    p99: Duration,
+    max: Duration,
+}
+
+impl Spread {
+    // nearest rank, None without samples
+    fn of(mut samples: Vec<Duration>) -> Option<Self> {
+        samples.sort();
+        let max = *samples.last()?;
+        let rank = |p: f64| samples[((p * samples.len() as f64).ceil() as usize).clamp(1, samples.len()) - 1];
+        Some(Self { p50: rank(0.5), p95: rank(0.95), p99: rank(0.99), max })
+    }
+
+    fn json(&self) -> String {
+        let ms = |d: Duration| format!("{:.4}", d.as_secs_f64() * 1000.);
+        format!(r#"{{"p50": {}, "p95": {}, "p99": {}, "max": {}}}"#, ms(self.p50), ms(self.p95), ms(self.p99), ms(self.max))
+    }
+}
+
+struct Report {
+    scenario: Scenario,
+    frames: usize,
+    /// wall time of the whole frame, waiting for the gpu included
+    frame: Option<Spread>,
+    cpu: Option<Spread>,
+    sim: Option<Spread>,
+    collision: Option<Spread>,
+    gpu: Option<Spread>,
+}
+
+impl Report {
+    fn parts(&self) -> [(&'static str, &Option<Spread>); 5] {
+        [("frame", &self.frame), ("cpu", &self.cpu), ("sim", &self.sim), ("collision", &self.collision), ("gpu", &self.gpu)]
+    }
+}
+
+/// run every scenario for `frames` frames, print the frame times & write them to `json` if given
+pub fn run(on_app_init: fn(&mut App) -> Result<(), Error>, scenarios: &[Scenario], frames: usize, json: Option<&Path>, record_fps: bool) -> Result<(), String> {
+    let mut reports = Vec::new();
+    let mut gpu_timer = false;
+    for scenario in scenarios {
+        let config = AppConfig { record_fps, ..scenario.config() };
+        let mut headless = Headless::new(on_app_init, config).map_err(|err| err.to_string())?;
+        gpu_timer = headless.app.has_gpu_timer();
+        let mut walls = Vec::with_capacity(frames);
+        for _ in 0..frames {
+            let start = Instant::now();
+            headless.render().map_err(|err| err.to_string())?;
+            // wait for the gpu too, queued commands would leak into the next frame otherwise
+            unsafe { headless.app.gl.finish() };
+            walls.push(start.elapsed());
+        }
+        let times = headless.app.frame_times().unwrap_or_default();
+        headless.app.on_exit();
+        reports.push(report(*scenario, &walls, &times));
+    }
+
+    println!("{:<16}{:<11}{:>12}{:>12}{:>12}{:>12}", "scenario", "part", "p50", "p95", "p99", "max");
+    for report in &reports {
+        for (part, spread) in report.parts() {
+            let Some(spread) = spread else {
+                continue;
+            };
+            let ms = |d: Duration| format!("{:.3}ms", d.as_secs_f64() * 1000.);
+            println!(
+                "{:<16}{:<11}{:>12}{:>12}{:>12}{:>12}",
+                report.scenario.name(),
+                part,
+                ms(spread.p50),
+                ms(spread.p95),
+                ms(spread.p99),
+                ms(spread.max)
+            );
+        }
+    }
+    if !gpu_timer {
+        println!("no GL timer queries on this context, gpu time left out");
+    }
+
+    if let Some(path) = json {
+        fs::write(path, to_json(&reports, gpu_timer)).map_err(|err| format!("can't write {} : {err}", path.display()))?;
+    }
+    Ok(())
+}
+
+fn report(scenario: Scenario, walls: &[Duration], times: &[FrameTimes]) -> Report {
+    let times = times.iter().skip(WARMUP_FRAMES);
+    let part = |f: fn(&FrameTimes) -> Duration| Spread::of(times.clone().map(f).collect());
+    // a frame without gpu time would skew the rest, all or nothing
+    let gpu = times.clone().map(|t| t.gpu).collect::<Option<Vec<_>>>().and_then(Spread::of);
+    Report {
+        scenario,
+        frames: walls.len().saturating_sub(WARMUP_FRAMES),
+        frame: Spread::of(walls.iter().skip(WARMUP_FRAMES).copied().collect()),
+        cpu: part(|t| t.cpu),
+        sim: part(|t| t.sim),
+        collision: part(|t| t.collision),
+        gpu,
+    }
+}
+
+// times in milliseconds, a part without samples is null
+fn to_json(reports: &[Report], gpu_timer: bool) -> String {
+    let scenarios = reports
+        .iter()
+        .map(|report| {
+            let parts = report
+                .parts()
+                .iter()
+                .map(|(part, spread)| {
+                    let spread = spread.as_ref().map_or_else(|| "null".to_string(), Spread::json);
+                    format!(r#"      "{part}_ms": {spread}"#)
+                })
+                .collect::<Vec<_>>()
+                .join(",\n");
+            format!("    \"{}\": {{\n      \"frames\": {},\n{parts}\n    }}", report.scenario.name(), report.frames)
+        })
+        .collect::<Vec<_>>()
+        .join(",\n");
+    format!(
+        "{{\n  \"version\": \"{}\",\n  \"warmup_frames\": {WARMUP_FRAMES},\n  \"gpu_timer\": {gpu_timer},\n  \"scenarios\": {{\n{scenarios}\n  }}\n}}\n",
+        env!("CARGO_PKG_VERSION")
+    )
+}
+
+// folded back & forth from the bottom left corner, the head leaves the top row to the right
+fn long_snek(grid: f32) -> SaveGame {
+    let mid = |col: usize, row: usize| Position { x: (col as f32 + 0.5) * grid, y: (row as f32 + 0.5) * grid };
+    // cells from the tail to the head
+    let mut path = Vec::new();
+    for row in 0..SERPENTINE_ROWS {
+        let cols = (0..SERPENTINE_WIDTH).collect::<Vec<_>>();
+        let cols = if row % 2 == 0 { cols } else { cols.into_iter().rev().collect() };
+        path.extend(cols.into_iter().map(|col| mid(col, row)));
+    }
+    let last_row = SERPENTINE_ROWS - 1;
+    path.extend((SERPENTINE_WIDTH..SERPENTINE_WIDTH + 2).map(|col| mid(col, last_row)));
+
+    let dir_between = |a: &Position, b: &Position| match (b.x - a.x, b.y - a.y) {
+        (x, _) if x > 0. => MoveDir::Right,
+        (x, _) if x < 0. => MoveDir::Left,
+        (_, y) if y > 0. => MoveDir::Up,
+        _ => MoveDir::Down,
+    };
+    let head = path.len() - 1;
+    let mut keypoints = Vec::new();
+    for i in 1..head {
+        let dir_in = dir_between(&path[i - 1], &path[i]);
+        if dir_in != dir_between(&path[i], &path[i + 1]) {
+            keypoints.push(DirKeypoint { from: dir_in.invert(), at: path[i].clone(), dst_head: (head - i) as f32 * grid });
+        }
+    }
+    SaveGame {
+        mode: GameMode::Zen,
+        rng: SEED,
+        snek: Some(SnekSave {
+            position: path[head].clone(),
+            dir: dir_between(&path[head - 1], &path[head]),
+            keypoints,
+            length: head as f32 * grid,
+            // slow enough for the body to stay folded the whole run
+            speed: 40.,
+            in_edge: Duration::ZERO,
+        }),
+        ..SaveGame::default()
+    }
+}
+
+// obstacles anywhere but the starting row of the snek
+fn many_obstacles(board: u16, grid: f32) -> SaveGame {
+    let cells = (board as f32 / grid) as usize;
+    let mid = |col: usize, row: usize| Position { x: (col as f32 + 0.5) * grid, y: (row as f32 + 0.5) * grid };
+    let start_row = cells / 2;
+    let mut rng = GameRng::new(SEED);
+    let mut obstacles: Vec<Position> = Vec::with_capacity(MAX_OBSTACLES);
+    while obstacles.len() < MAX_OBSTACLES {
+        let (col, row) = (rng.random_range(0..cells), rng.random_range(0..cells));
+        let cell = mid(col, row);
+        if row.abs_diff(start_row) > 1 && !obstacles.contains(&cell) {
+            obstacles.push(cell);
+        }
+    }
+    SaveGame {
+        mode: GameMode::Survival,
+        rng: SEED,
+        snek: Some(SnekSave {
+            position: mid(cells / 2, start_row),
+            dir: MoveDir::Left,
+            keypoints: Vec::new(),
+            length: 100.,
+            speed: 120.,
+            in_edge: Duration::ZERO,
+        }),
+        obstacles,
+        until_next_obstacle: GameMode::Survival.obstacle_interval().unwrap_or_default(),
+        ..SaveGame::default()
+    }
+}
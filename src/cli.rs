@@ -0,0 +1,297 @@
+use std::{path::PathBuf, str::FromStr, time::Duration};
+
+use crate::{
+    app::AppConfig,
+    audio::AudioBackend,
+    bench::Scenario,
+    objects::{bot::BotStrategy, game_mode::GameMode},
+    MAX_FPS,
+};
+
+// the board needs room for the snek to turn around
+const MIN_CELLS: u16 = 4;
+const DEFAULT_TICKS: usize = 3600;
+const DEFAULT_BENCH_FRAMES: usize = 2000;
+
+pub const USAGE: &str = "\
+usage: gl_snake [--record-fps] [command]
+
+commands:
+  play [options]          play in a window, the default
+    --width <px>          window width
+    --height <px>         window height
+    --grid <px>           size of a board cell
+    --board <cols>x<rows> board bigger than the window, the view follows the snek
+    --speed <factor>      starting speed, relative to the normal one
+    --mode <mode>         classic, time-attack, survival or zen
+    --seed <n>            every run gets the same food & power ups
+    --record <file>       keep the first run to watch it again
+    --audio <file>        write the sound to a WAV file instead of playing it
+  replay <file>           watch a recorded run
+  bench [options]         time scripted runs without a window, frame time percentiles
+    --frames <n>          frames per scenario
+    --scenario <name>     classic, long-snek, many-foods or many-colliders, all of them otherwise
+    --json <file>         also write the results there
+  headless [options]      let a bot play without a window, takes the play options & :
+    --ticks <n>           frames to run for, 1/60 s each
+    --bot <strategy>      greedy or random
+  scores                  best runs by mode
+
+  --record-fps            print the frame rate every 50 frames & its average on exit
+  --help                  this message";
+
+pub enum Command {
+    Play(AppConfig),
+    Replay(PathBuf),
+    Bench { frames: usize, scenarios: Vec<Scenario>, json: Option<PathBuf> },
+    Headless { config: AppConfig, ticks: usize },
+    Scores,
+    Help,
+}
+
+pub struct Cli {
+    pub command: Command,
+    /// switches `AppConfig::record_fps` on, whatever the command
+    pub record_fps: bool,
+}
+
+/// `args` without the program name
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Cli, String> {
+    let mut record_fps = false;
+    let mut help = false;
+    let mut rest = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--record-fps" => record_fps = true,
+            "--help" | "-h" => help = true,
+            _ => rest.push(arg),
+        }
+    }
+    if help {
+        return Ok(Cli { command: Command::Help, record_fps });
+    }
+
+    let mut args = Args(rest.into_iter());
+    let command = match args.0.next().as_deref() {
+        None => Command::Play(AppConfig::default()),
+        Some("play") => {
+            let mut config = AppConfig::default();
+            while let Some(flag) = args.0.next() {
+                if !parse_run_option(&mut config, &flag, &mut args)? {
+                    return Err(format!("unknown option `{flag}` for play"));
+                }
+            }
+            Command::Play(check_board(config)?)
+        }
+        Some("replay") => {
+            let file = args.0.next().ok_or("replay needs a file")?;
+            args.end("replay")?;
+            Command::Replay(file.into())
+        }
+        Some("bench") => {
+            let mut frames = DEFAULT_BENCH_FRAMES;
+            let mut scenarios = Vec::new();
+            let mut json = None;
+            while let Some(flag) = args.0.next() {
+                match flag.as_str() {
+                    "--frames" => frames = args.value(&flag)?,
+                    "--scenario" => {
+                        let name: String = args.value(&flag)?;
+                        scenarios.push(Scenario::from_arg(&name).ok_or_else(|| format!("unknown scenario `{name}`"))?);
+                    }
+                    "--json" => json = Some(args.value(&flag)?),
+                    _ => return Err(format!("unknown option `{flag}` for bench")),
+                }
+            }
+            if scenarios.is_empty() {
+                scenarios = Scenario::ALL.to_vec();
+            }
+            Command::Bench { frames, scenarios, json }
+        }
+        Some("headless") => {
+            let mut config = AppConfig {
+                skip_title: true,
+                fixed_delta: Some(Duration::from_secs_f32(1. / MAX_FPS)),
+                audio: AudioBackend::Null,
+                ..AppConfig::default()
+            };
+            config.settings.persist = false;
+            let mut ticks = DEFAULT_TICKS;
+            let mut bot = BotStrategy::Greedy;
+            while let Some(flag) = args.0.next() {
+                if !parse_run_option(&mut config, &flag, &mut args)? {
+                    match flag.as_str() {
+                        "--ticks" => ticks = args.value(&flag)?,
+                        "--bot" => {
+                            let name: String = args.value(&flag)?;
+                            bot = BotStrategy::from_arg(&name).ok_or_else(|| format!("unknown bot strategy `{name}`"))?;
+                        }
+                        _ => return Err(format!("unknown option `{flag}` for headless")),
+                    }
+                }
+            }
+            config.settings.bot = Some(bot);
+            Command::Headless { config: check_board(config)?, ticks }
+        }
+        Some("scores") => {
+            args.end("scores")?;
+            Command::Scores
+        }
+        Some(other) => return Err(format!("unknown command `{other}`")),
+    };
+    Ok(Cli { command, record_fps })
+}
+
+// options shared by `play` & `headless`, false when `flag` isn't one of them
+fn parse_run_option(config: &mut AppConfig, flag: &str, args: &mut Args) -> Result<bool, String> {
+    match flag {
+        "--width" => config.width = args.value(flag)?,
+        "--height" => config.height = args.value(flag)?,
+        "--grid" => config.grid_size = args.value(flag)?,
+        "--speed" => {
+            let speed: f32 = args.value(flag)?;
+            if !(speed.is_finite() && speed > 0.) {
+                return Err(format!("--speed has to be above 0, got {speed}"));
+            }
+            config.settings.speed = speed;
+        }
+        "--mode" => {
+            let name: String = args.value(flag)?;
+            config.settings.mode = GameMode::from_arg(&name).ok_or_else(|| format!("unknown mode `{name}`"))?;
+        }
+        "--seed" => config.settings.seed = Some(args.value(flag)?),
+        "--record" => {
+            config.record = Some(args.value(flag)?);
+            // the recording starts with the run, not the title screen
+            config.skip_title = true;
+        }
+        "--board" => {
+            let value: String = args.value(flag)?;
+            let cells = value.split_once('x').and_then(|(cols, rows)| Some((cols.parse().ok()?, rows.parse().ok()?)));
+            config.board = Some(cells.ok_or_else(|| format!("--board takes columns x rows, eg 200x200, got `{value}`"))?);
+        }
+        "--audio" => config.audio = AudioBackend::Wav(args.value(flag)?),
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+fn check_board(config: AppConfig) -> Result<AppConfig, String> {
+    let cells = |size: u16| size.checked_div(config.grid_size).unwrap_or(0);
+    let (cols, rows) = config.board.unwrap_or((cells(config.width), cells(config.height)));
+    if cols < MIN_CELLS || rows < MIN_CELLS {
+        return Err(format!("the board needs at least {MIN_CELLS} cells each way"));
+    }
+    if cols.checked_mul(config.grid_size).zip(rows.checked_mul(config.grid_size)).is_none() {
+        return Err(format!("a {cols}x{rows} board is too big with {} pixel cells", config.grid_size));
+    }
+    Ok(config)
+}
+
+struct Args(std::vec::IntoIter<String>);
+
+impl Args {
+    fn value<T: FromStr>(&mut self, flag: &str) -> Result<T, String> {
+        let value = self.0.next().ok_or_else(|| format!("{flag} needs a value"))?;
+        value.parse().map_err(|_| format!("can't read `{value}` for {flag}"))
+    }
+    fn end(&mut self, command: &str) -> Result<(), String> {
+        match self.0.next() {
+            Some(extra) => Err(format!("unexpected `{extra}` after {command}")),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    fn cli(args: &str) -> Result<Cli, String> {
+        parse(args.split_whitespace().map(String::from))
+    }
+
+    fn err(args: &str) -> String {
+        match cli(args) {
+            Ok(_) => panic!("`{args}` shouldn't parse"),
+            Err(err) => err,
+        }
+    }
+
+    #[test]
+    fn commands() {
+        assert!(matches!(cli("").unwrap().command, Command::Play(_)));
+        assert!(matches!(cli("play").unwrap().command, Command::Play(_)));
+        assert!(matches!(cli("replay run.txt").unwrap().command, Command::Replay(path) if path == Path::new("run.txt")));
+        assert!(matches!(cli("scores").unwrap().command, Command::Scores));
+        assert!(matches!(cli("play --help").unwrap().command, Command::Help));
+
+        let Command::Bench { frames, scenarios, json } = cli("bench").unwrap().command else {
+            panic!("bench expected");
+        };
+        assert_eq!((frames, scenarios, json), (DEFAULT_BENCH_FRAMES, Scenario::ALL.to_vec(), None));
+
+        let Command::Headless { config, ticks } = cli("headless").unwrap().command else {
+            panic!("headless expected");
+        };
+        assert_eq!(ticks, DEFAULT_TICKS);
+        assert_eq!(config.settings.bot, Some(BotStrategy::Greedy));
+        assert!(config.skip_title && !config.settings.persist);
+
+        let cli = cli("--record-fps scores").unwrap();
+        assert!(cli.record_fps && matches!(cli.command, Command::Scores));
+    }
+
+    #[test]
+    fn options() {
+        let Command::Play(config) = cli("play --speed 1.5 --mode time-attack --seed 9 --board 30x20 --grid 20").unwrap().command else {
+            panic!("play expected");
+        };
+        assert_eq!(config.settings.speed, 1.5);
+        assert_eq!(config.settings.mode, GameMode::TimeAttack);
+        assert_eq!(config.settings.seed, Some(9));
+        assert_eq!(config.board, Some((30, 20)));
+        assert_eq!(config.grid_size, 20);
+
+        let Command::Headless { config, ticks } = cli("headless --ticks 60 --bot random --speed 2").unwrap().command else {
+            panic!("headless expected");
+        };
+        assert_eq!(ticks, 60);
+        assert_eq!(config.settings.bot, Some(BotStrategy::Random));
+        assert_eq!(config.settings.speed, 2.);
+
+        let Command::Bench { frames, scenarios, .. } = cli("bench --frames 10 --scenario long-snek").unwrap().command else {
+            panic!("bench expected");
+        };
+        assert_eq!((frames, scenarios), (10, vec![Scenario::LongSnek]));
+    }
+
+    #[test]
+    fn speed_errors() {
+        assert!(err("play --speed 0").contains("above 0"));
+        assert!(err("play --speed -1").contains("above 0"));
+        assert!(err("play --speed inf").contains("above 0"));
+        assert_eq!(err("play --speed fast"), "can't read `fast` for --speed");
+        assert_eq!(err("play --speed"), "--speed needs a value");
+    }
+
+    #[test]
+    fn board_errors() {
+        assert!(err("play --board 30").contains("columns x rows"));
+        assert!(err("play --board 30xa").contains("columns x rows"));
+        assert!(err("play --board 3x30").contains("at least"));
+        assert!(err("headless --board 5000x5000 --grid 40").contains("too big"));
+    }
+
+    #[test]
+    fn unknown_errors() {
+        assert_eq!(err("play --fast"), "unknown option `--fast` for play");
+        assert_eq!(err("headless --fast"), "unknown option `--fast` for headless");
+        assert_eq!(err("bench --fast"), "unknown option `--fast` for bench");
+        assert_eq!(err("fly"), "unknown command `fly`");
+        assert_eq!(err("scores now"), "unexpected `now` after scores");
+        assert_eq!(err("headless --bot clever"), "unknown bot strategy `clever`");
+    }
+}
@@ -0,0 +1,50 @@
+use std::{fmt, io, path::PathBuf};
+
+/// whatever stops the game from starting or going on, printed before exiting
+#[derive(Debug)]
+pub enum Error {
+    /// winit couldn't run its event loop or open the window
+    Window(String),
+    /// the display has no pixel format the window can draw with
+    NoGlConfig,
+    /// every context version got refused, in the order they were tried
+    GlContext(Vec<(&'static str, glutin::error::Error)>),
+    /// a GL or glutin call failed, with what was being done at the time
+    Gl(&'static str, String),
+    /// running without a window, see `Headless`
+    Offscreen(String),
+    /// shaders are read from `shader/` in the working directory
+    ShaderFile { path: PathBuf, source: io::Error },
+    ShaderCompile { name: String, stage: &'static str, log: String },
+    ShaderLink { name: String, log: String },
+    /// the shader doesn't declare it, or the driver optimized it out
+    MissingUniform { shader: String, uniform: String },
+    MissingAttribute(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Window(err) => write!(f, "can't open the window : {err}"),
+            Self::NoGlConfig => write!(f, "the display has no OpenGL config the window can use"),
+            Self::GlContext(tried) => {
+                write!(f, "can't create an OpenGL context, the game needs OpenGL 3.3, tried :")?;
+                for (version, err) in tried {
+                    write!(f, "\n  {version} : {err}")?;
+                }
+                Ok(())
+            }
+            Self::Gl(what, err) => write!(f, "{what} : {err}"),
+            Self::Offscreen(err) => write!(f, "can't render offscreen : {err}"),
+            Self::ShaderFile { path, source } => {
+                write!(f, "can't read {} : {source}, the game has to run from the directory holding `shader/`", path.display())
+            }
+            Self::ShaderCompile { name, stage, log } => write!(f, "the {stage} shader of `{name}` doesn't compile :\n{}", log.trim_end()),
+            Self::ShaderLink { name, log } => write!(f, "the `{name}` shader doesn't link :\n{}", log.trim_end()),
+            Self::MissingUniform { shader, uniform } => write!(f, "the `{shader}` shader has no `{uniform}` uniform"),
+            Self::MissingAttribute(name) => write!(f, "the shader has no `{name}` attribute"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
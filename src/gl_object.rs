@@ -0,0 +1,115 @@
+use std::{ops::Deref, rc::Rc};
+
+use glow::{HasContext, NativeBuffer, NativeFramebuffer, NativeProgram, NativeQuery, NativeShader, NativeTexture, NativeVertexArray};
+
+use crate::error::Error;
+
+/// a GL object type `GlObject` knows how to delete
+pub trait GlKind: Copy {
+    // shown when creating one fails
+    const CREATE_FAILED: &'static str;
+    // index in the leak counters
+    const INDEX: usize;
+    unsafe fn delete(self, gl: &glow::Context);
+}
+
+macro_rules! gl_kind {
+    ($($ty:ty => $index:literal, $name:literal, $delete:ident;)*) => {
+        $(impl GlKind for $ty {
+            const CREATE_FAILED: &'static str = concat!("can't create a GL ", $name);
+            const INDEX: usize = $index;
+            unsafe fn delete(self, gl: &glow::Context) {
+                unsafe { gl.$delete(self) }
+            }
+        })*
+        #[cfg(debug_assertions)]
+        const KIND_NAMES: [&str; 7] = [$($name),*];
+    };
+}
+
+gl_kind! {
+    NativeBuffer => 0, "buffer", delete_buffer;
+    NativeVertexArray => 1, "vertex array", delete_vertex_array;
+    NativeProgram => 2, "program", delete_program;
+    NativeShader => 3, "shader", delete_shader;
+    NativeTexture => 4, "texture", delete_texture;
+    NativeFramebuffer => 5, "framebuffer", delete_framebuffer;
+    NativeQuery => 6, "query", delete_query;
+}
+
+#[cfg(debug_assertions)]
+mod leaks {
+    use std::sync::atomic::{AtomicIsize, Ordering};
+
+    // GL objects alive right now, by kind
+    static LIVE: [AtomicIsize; 7] = [const { AtomicIsize::new(0) }; 7];
+
+    pub fn add(index: usize, n: isize) {
+        LIVE[index].fetch_add(n, Ordering::Relaxed);
+    }
+    pub fn live() -> [isize; 7] {
+        std::array::from_fn(|i| LIVE[i].load(Ordering::Relaxed))
+    }
+}
+
+// only counted in debug builds
+#[cfg(not(debug_assertions))]
+mod leaks {
+    pub fn add(_index: usize, _n: isize) {}
+}
+
+/// owns a GL object & deletes it when dropped, keeps the context alive until then <br/>
+/// has to be dropped while its context is still current
+pub struct GlObject<T: GlKind> {
+    gl: Rc<glow::Context>,
+    raw: T,
+}
+
+impl<T: GlKind> GlObject<T> {
+    /// `create` is the glow call making the object, eg `|gl| unsafe { gl.create_buffer() }`
+    pub fn create(gl: &Rc<glow::Context>, create: impl FnOnce(&glow::Context) -> Result<T, String>) -> Result<Self, Error> {
+        let raw = create(gl).map_err(|err| Error::Gl(T::CREATE_FAILED, err))?;
+        leaks::add(T::INDEX, 1);
+        Ok(Self { gl: gl.clone(), raw })
+    }
+}
+
+impl<T: GlKind> Deref for GlObject<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.raw
+    }
+}
+
+impl<T: GlKind> Drop for GlObject<T> {
+    fn drop(&mut self) {
+        unsafe { self.raw.delete(&self.gl) };
+        leaks::add(T::INDEX, -1);
+    }
+}
+
+// "2 buffer, 1 program", None when nothing is alive
+#[cfg(debug_assertions)]
+fn live_objects() -> Option<String> {
+    let live = KIND_NAMES.iter().zip(leaks::live()).filter(|(_, n)| *n != 0).map(|(name, n)| format!("{n} {name}")).collect::<Vec<_>>();
+    (!live.is_empty()).then(|| live.join(", "))
+}
+
+/// GL objects alive right now, by kind, logged at debug in debug builds <br/>
+/// should stay the same from one run to the next
+pub fn log_live(when: &'static str) {
+    #[cfg(debug_assertions)]
+    crate::log::event!(Debug, "gl", "GL objects alive", when = when, objects = live_objects().unwrap_or_default());
+    #[cfg(not(debug_assertions))]
+    let _ = when;
+}
+
+/// warns about every GL object never deleted, in debug builds <br/>
+/// to be called once the app & its context are gone
+pub fn check_leaks() {
+    #[cfg(debug_assertions)]
+    match live_objects() {
+        Some(leaked) => crate::log::event!(Warn, "gl", "GL objects leaked", objects = leaked),
+        None => crate::log::event!(Debug, "gl", "no GL object leaked"),
+    }
+}
@@ -0,0 +1,171 @@
+use std::{borrow::Cow, cell::RefCell, fmt::Debug, fs::File, io::{LineWriter, Write}, sync::{Mutex, OnceLock}, time::Instant};
+
+/// comma separated, a level for everything &/or `target=level` overrides, eg `warn,input=debug`
+const FILTER_VAR: &str = "GL_SNAKE_LOG";
+/// log lines go to that file instead of stderr, truncated on launch
+const FILE_VAR: &str = "GL_SNAKE_LOG_FILE";
+const DEFAULT_LEVEL: Level = Level::Warn;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Error => "ERROR",
+            Self::Warn => "WARN",
+            Self::Info => "INFO",
+            Self::Debug => "DEBUG",
+            Self::Trace => "TRACE",
+        }
+    }
+    // None means off
+    fn parse(arg: &str) -> Result<Option<Self>, ()> {
+        match arg.to_ascii_lowercase().as_str() {
+            "off" => Ok(None),
+            "error" => Ok(Some(Self::Error)),
+            "warn" => Ok(Some(Self::Warn)),
+            "info" => Ok(Some(Self::Info)),
+            "debug" => Ok(Some(Self::Debug)),
+            "trace" => Ok(Some(Self::Trace)),
+            _ => Err(()),
+        }
+    }
+}
+
+// set up from the environment on the first line logged
+struct Logger {
+    default: Option<Level>,
+    targets: Vec<(String, Option<Level>)>,
+    file: Option<Mutex<LineWriter<File>>>,
+    start: Instant,
+}
+
+impl Logger {
+    fn from_env() -> Self {
+        let mut logger = Self { default: Some(DEFAULT_LEVEL), targets: Vec::new(), file: None, start: Instant::now() };
+        if let Ok(filter) = std::env::var(FILTER_VAR) {
+            for part in filter.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+                let parsed = match part.split_once('=') {
+                    Some((target, level)) => Level::parse(level).map(|level| logger.targets.push((target.to_string(), level))),
+                    None => Level::parse(part).map(|level| logger.default = level),
+                };
+                if parsed.is_err() {
+                    eprintln!("{FILTER_VAR} : can't read `{part}`, levels are off, error, warn, info, debug & trace");
+                }
+            }
+        }
+        if let Some(path) = std::env::var_os(FILE_VAR) {
+            match File::create(&path) {
+                Ok(file) => logger.file = Some(Mutex::new(LineWriter::new(file))),
+                Err(err) => eprintln!("{FILE_VAR} : can't open {} , logging to stderr : {err}", path.display()),
+            }
+        }
+        logger
+    }
+
+    fn enabled(&self, level: Level, target: &str) -> bool {
+        // the last override wins
+        let max = self.targets.iter().rev().find(|(t, _)| t == target).map_or(self.default, |(_, level)| *level);
+        max.is_some_and(|max| level <= max)
+    }
+
+    fn write_line(&self, line: &str) {
+        match &self.file {
+            Some(file) => {
+                if let Ok(mut file) = file.lock() {
+                    let _ = writeln!(file, "{line}");
+                }
+            }
+            None => eprintln!("{line}"),
+        }
+    }
+}
+
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+
+thread_local! {
+    // spans entered on this thread, outermost first
+    static SPANS: RefCell<Vec<Cow<'static, str>>> = const { RefCell::new(Vec::new()) };
+}
+
+fn logger() -> &'static Logger {
+    LOGGER.get_or_init(Logger::from_env)
+}
+
+pub fn enabled(level: Level, target: &str) -> bool {
+    logger().enabled(level, target)
+}
+
+/// one line : time since launch, level, target, the spans it happened in, the message & its fields <br/>
+/// use `event!`, it skips formatting the fields when the level is filtered out
+pub fn write(level: Level, target: &str, message: &str, fields: &[(&str, &dyn Debug)]) {
+    let logger = logger();
+    let spans = SPANS.with_borrow(|spans| spans.iter().map(|s| format!("{s} > ")).collect::<String>());
+    let mut line = format!("{:>9.3} {:<5} {target}: {spans}{message}", logger.start.elapsed().as_secs_f64(), level.name());
+    for (key, value) in fields {
+        line.push_str(&format!(" {key}={value:?}"));
+    }
+    logger.write_line(&line);
+}
+
+/// time between entering & dropping it, every line logged meanwhile is prefixed with its name <br/>
+/// entering & leaving are logged with its level, the fields are only kept when that level is enabled
+pub struct Span {
+    level: Level,
+    target: &'static str,
+    start: Instant,
+    enabled: bool,
+}
+
+impl Span {
+    /// use `span!`
+    pub fn enter(level: Level, target: &'static str, name: &'static str, fields: &[(&str, &dyn Debug)]) -> Self {
+        let enabled = enabled(level, target);
+        let label = if enabled && !fields.is_empty() {
+            let fields = fields.iter().map(|(key, value)| format!("{key}={value:?}")).collect::<Vec<_>>();
+            Cow::Owned(format!("{name}{{{}}}", fields.join(",")))
+        } else {
+            Cow::Borrowed(name)
+        };
+        SPANS.with_borrow_mut(|spans| spans.push(label));
+        if enabled {
+            write(level, target, "enter", &[]);
+        }
+        Self { level, target, start: Instant::now(), enabled }
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed();
+        if self.enabled {
+            write(self.level, self.target, "exit", &[("took", &elapsed)]);
+        }
+        SPANS.with_borrow_mut(|spans| spans.pop());
+    }
+}
+
+/// `event!(Level, "target", "message", key = value, ..)`, values are printed with `Debug`
+macro_rules! event {
+    ($level:ident, $target:literal, $message:literal $(, $key:ident = $value:expr)* $(,)?) => {
+        if $crate::log::enabled($crate::log::Level::$level, $target) {
+            $crate::log::write($crate::log::Level::$level, $target, $message, &[$((stringify!($key), &$value as &dyn std::fmt::Debug)),*])
+        }
+    };
+}
+
+/// `let _span = span!(Level, "target", "name", key = value, ..)`, lasts until dropped
+macro_rules! span {
+    ($level:ident, $target:literal, $name:literal $(, $key:ident = $value:expr)* $(,)?) => {
+        $crate::log::Span::enter($crate::log::Level::$level, $target, $name, &[$((stringify!($key), &$value as &dyn std::fmt::Debug)),*])
+    };
+}
+
+pub(crate) use {event, span};
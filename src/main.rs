@@ -1,31 +1,158 @@
-use std::rc::Rc;
+use std::{fs, path::Path, process::ExitCode, time::{Duration, Instant}};
 
 use crate::{
     app::{
-        app_owned_data::{AppOwnedData, CldrUpdt, CldrUpdtInpLstr, Updateable, UpdtInpLstr}, App, AppBootstraper
+        app_owned_data::{AppOwnedData, CldrUpdt, CldrUpdtInpLstr, Updateable}, headless::Headless, recording::Recording, App, AppBootstraper, AppConfig, ColliderLayer
     },
-    objects::{food::Food, snek::Snek},
-    shaders::SnekShader,
+    cli::Command,
+    error::Error,
+    objects::{food::Food, game_mode::GameMode, high_scores, food_spawner::SpawnRule, grid::Grid, hud::Hud, obstacles::Obstacles, particles::Particles, power_up::PowerUp, save_game::SaveGame, snek::Snek, sprites::Sprites, GameContext},
 };
 
 mod app;
+mod audio;
+mod bench;
+mod cli;
+mod error;
+mod gl_object;
+mod log;
 mod meshes;
 mod objects;
+mod records;
 mod shaders;
 
 pub const MAX_FPS : f32 = 60.; 
 pub const WINDOW_WIDTH : u16 = 400;
 pub const WINDOW_HEIGHT : u16 = 400;
+pub const GRID_SIZE : u16 = 25;
+pub const FOOD_COUNT : usize = 3;
+/// where the run in progress is kept on quit
+pub const SAVE_PATH : &str = "./snek.save";
+/// every finished run is added there
+pub const SCORES_PATH : &str = "./snek.scores";
+// best runs listed per mode by `scores`
+const SCORES_SHOWN : usize = 10;
 
-fn main() {
-    let gl_app = AppBootstraper::new(on_app_init);
-    gl_app.exec().unwrap();
+fn main() -> ExitCode {
+    let cli = match cli::parse(std::env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(err) => {
+            eprintln!("{err}\n\n{}", cli::USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    let with_fps = |mut config: AppConfig| {
+        config.record_fps |= cli.record_fps;
+        config
+    };
+    let res = match cli.command {
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            Ok(())
+        }
+        Command::Play(config) => play(with_fps(config)).map_err(|err| err.to_string()),
+        Command::Replay(path) => fs::read_to_string(&path)
+            .map_err(|err| format!("can't read {} : {err}", path.display()))
+            .and_then(|text| Recording::parse(&text).map_err(|err| format!("{} isn't a valid recording : {err}", path.display())))
+            .and_then(|replay| play(with_fps(AppConfig::replaying(replay))).map_err(|err| err.to_string())),
+        Command::Bench { frames, scenarios, json } => bench::run(on_app_init, &scenarios, frames, json.as_deref(), cli.record_fps),
+        Command::Headless { config, ticks } => headless(with_fps(config), ticks).map_err(|err| err.to_string()),
+        Command::Scores => scores(SCORES_PATH),
+    };
+    gl_object::check_leaks();
+    match res {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
 }
 
-fn on_app_init(app: &mut App) {
-    let basic = Rc::new(SnekShader::new(&app.gl));
-    let square = Snek::new(app, basic.clone());
-    let food = Food::new(&app.gl);
+fn play(config: AppConfig) -> Result<(), Error> {
+    AppBootstraper::new(on_app_init, config).exec()
+}
+
+fn headless(config: AppConfig, ticks: usize) -> Result<(), Error> {
+    let mut headless = Headless::new(on_app_init, config)?;
+    let start = Instant::now();
+    for _ in 0..ticks {
+        headless.render()?;
+    }
+    println!("{ticks} ticks in {:.2?}", start.elapsed());
+    headless.app.on_exit();
+    Ok(())
+}
+
+fn scores(path: impl AsRef<Path>) -> Result<(), String> {
+    let path = path.as_ref();
+    let scores = high_scores::load(path).map_err(|err| format!("can't read {} : {err}", path.display()))?;
+    if scores.is_empty() {
+        println!("no finished runs yet");
+        return Ok(());
+    }
+    for mode in GameMode::ALL {
+        let best = scores.iter().filter(|s| s.mode == mode).take(SCORES_SHOWN).collect::<Vec<_>>();
+        if best.is_empty() {
+            continue;
+        }
+        println!("{}", mode.name());
+        for (rank, score) in best.iter().enumerate() {
+            println!(
+                "{:>3}. {:>6}  FOOD {:<4} TIME {:.0}S",
+                rank + 1,
+                score.points,
+                score.food_eaten,
+                score.elapsed.as_secs_f32()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn on_app_init(app: &mut App) -> Result<(), Error> {
+    // eating & picking up is decided by the food & power ups, running into an obstacle by the snek
+    app.collision_matrix
+        .allow(ColliderLayer::Food, ColliderLayer::Player)
+        .allow(ColliderLayer::PowerUp, ColliderLayer::Player)
+        .allow(ColliderLayer::Player, ColliderLayer::Obstacle)
+        .allow_self(ColliderLayer::Player);
+    let resume = app.resume.take().and_then(|save| match SaveGame::parse(&save) {
+        Ok(save) => Some(save),
+        Err(err) => {
+            log::event!(Warn, "app", "can't resume the saved run, starting a new one", error = err);
+            None
+        }
+    });
+    let ctx = GameContext::new(app, vec![
+        SpawnRule::Delay(Duration::from_millis(800)),
+        SpawnRule::AwayFromHead(3.),
+    ], resume);
+    let run = ctx.clone();
+    app.save_run = Some(Box::new(move || run.snapshot()));
+    // first so it is drawn under everything else
+    let grid = Grid::new(&mut app.assets, &ctx)?;
+    app.take(AppOwnedData::from(Box::new(grid) as Box<dyn Updateable>));
+    let square = Snek::new(&mut app.assets, &ctx)?;
+    let power_up = PowerUp::new(&ctx);
     app.take(AppOwnedData::from(Box::new(square) as Box<dyn CldrUpdtInpLstr>));
-    app.take(AppOwnedData::from(Box::new(food) as Box<dyn CldrUpdt>));
-}
\ No newline at end of file
+    let food_count = app.settings.borrow().food_count;
+    for slot in 0..food_count {
+        let food = Food::new(slot, &ctx);
+        app.take(AppOwnedData::from(Box::new(food) as Box<dyn CldrUpdt>));
+    }
+    app.take(AppOwnedData::from(Box::new(power_up) as Box<dyn CldrUpdt>));
+    if let Some(interval) = ctx.mode.obstacle_interval() {
+        let obstacles = Obstacles::new(interval, &ctx);
+        app.take(AppOwnedData::from(Box::new(obstacles) as Box<dyn CldrUpdt>));
+    }
+    // after everything it draws
+    let sprites = Sprites::new(&mut app.assets, &ctx)?;
+    app.take(AppOwnedData::from(Box::new(sprites) as Box<dyn Updateable>));
+    let particles = Particles::new(&mut app.assets, &ctx)?;
+    app.take(AppOwnedData::from(Box::new(particles) as Box<dyn Updateable>));
+    // last so it is drawn over everything else
+    let hud = Hud::new(&mut app.assets, &ctx)?;
+    app.take(AppOwnedData::from(Box::new(hud) as Box<dyn Updateable>));
+    Ok(())
+}
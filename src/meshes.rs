@@ -1,3 +1,5 @@
 mod unit_rect;
+mod instanced_rect;
 
-pub use unit_rect::UnitRect;
\ No newline at end of file
+pub use unit_rect::UnitRect;
+pub use instanced_rect::InstancedRect;
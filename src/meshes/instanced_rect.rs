@@ -0,0 +1,103 @@
+use std::{mem, rc::Rc};
+
+use glow::{HasContext, NativeBuffer, NativeVertexArray};
+
+use crate::{error::Error, gl_object::GlObject, shaders::Shader};
+
+/// rect from -1 to 1 drawn once per instance, every instance brings its own attributes <br/>
+/// the vertex shader is responsible for placing each instance
+pub struct InstancedRect {
+    vao : GlObject<NativeVertexArray>,
+    // only referenced through the vao, kept until it goes
+    _vbo : GlObject<NativeBuffer>,
+    _ebo : GlObject<NativeBuffer>,
+    instance_vbo : GlObject<NativeBuffer>,
+    // floats per instance
+    stride : usize,
+}
+
+impl InstancedRect {
+    /// `layout` lists the per instance attributes in order, as name & float count
+    pub fn new(gl : &Rc<glow::Context>, shader : &impl Shader, layout : &[(&str, i32)]) -> Result<Self, Error> {
+        let stride = layout.iter().map(|(_, size)| *size as usize).sum::<usize>();
+        let (vbo, vao, ebo, instance_vbo) = unsafe {
+            let vert = [
+                -1.,  1., 0.0,   // top left 
+                -1., -1., 0.0,  // bottom left
+                1., -1., 0.0,  // bottom right
+                1.0f32,  1., 0.0,  // top right
+            ];
+            let vert: &[u8] = core::slice::from_raw_parts(
+                vert.as_ptr() as *const u8,
+                vert.len() * core::mem::size_of::<f32>(),
+            );
+            let indices = [
+                0u32, 1, 3,
+                1, 2, 3 
+            ];
+            let indices = core::slice::from_raw_parts(
+                indices.as_ptr() as *const u8,
+                indices.len() * mem::size_of::<u32>()
+            );
+
+            let vao = GlObject::create(gl, |gl| gl.create_vertex_array())?;
+            let vbo = GlObject::create(gl, |gl| gl.create_buffer())?;
+            let ebo = GlObject::create(gl, |gl| gl.create_buffer())?;
+            let instance_vbo = GlObject::create(gl, |gl| gl.create_buffer())?;
+            gl.bind_vertex_array(Some(*vao));
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(*vbo));
+            gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(*ebo));
+            gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, vert, glow::STATIC_DRAW);
+            gl.buffer_data_u8_slice(glow::ELEMENT_ARRAY_BUFFER, indices, glow::STATIC_DRAW);
+
+            let pos_attr = shader.get_attribute("aPos").ok_or_else(|| Error::MissingAttribute("aPos".to_string()))?;
+            gl.enable_vertex_attrib_array(pos_attr);
+            gl.vertex_attrib_pointer_f32(pos_attr, 3, glow::FLOAT, false, 3 * mem::size_of::<f32>() as i32, 0);
+
+            // per instance attributes, interleaved in a single buffer
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(*instance_vbo));
+            let mut offset = 0;
+            for (name, size) in layout {
+                let attr = shader.get_attribute(name).ok_or_else(|| Error::MissingAttribute(name.to_string()))?;
+                gl.enable_vertex_attrib_array(attr);
+                gl.vertex_attrib_pointer_f32(
+                    attr,
+                    *size,
+                    glow::FLOAT,
+                    false,
+                    (stride * mem::size_of::<f32>()) as i32,
+                    offset * mem::size_of::<f32>() as i32,
+                );
+                gl.vertex_attrib_divisor(attr, 1);
+                offset += size;
+            }
+
+            gl.bind_vertex_array(None);
+            gl.bind_buffer(glow::ARRAY_BUFFER, None);
+            gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, None);
+            (vbo, vao, ebo, instance_vbo)
+        };
+
+        Ok(Self { vao, _vbo: vbo, _ebo: ebo, instance_vbo, stride })
+    }
+
+    /// `instances` holds the attributes of every instance back to back, following the layout
+    pub fn render(&self, gl : &glow::Context, instances : &[f32]) {
+        let count = instances.len() / self.stride;
+        if count == 0 {
+            return;
+        }
+        unsafe {
+            let data = core::slice::from_raw_parts(
+                instances.as_ptr() as *const u8,
+                mem::size_of_val(instances),
+            );
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(*self.instance_vbo));
+            gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, data, glow::STREAM_DRAW);
+            gl.bind_buffer(glow::ARRAY_BUFFER, None);
+
+            gl.bind_vertex_array(Some(*self.vao));
+            gl.draw_elements_instanced(glow::TRIANGLES, 6, glow::UNSIGNED_INT, 0, count as i32);
+        }
+    }
+}
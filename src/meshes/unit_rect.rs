@@ -1,18 +1,18 @@
-use core::time;
 use std::{mem, rc::Rc};
 
 use glow::{HasContext, NativeBuffer, NativeVertexArray};
 
-use crate::shaders::Shader;
+use crate::{error::Error, gl_object::GlObject, shaders::Shader};
 
 pub struct UnitRect {
-    vao : NativeVertexArray,
-    vbo : NativeBuffer, 
-    ebo : NativeBuffer,
+    vao : GlObject<NativeVertexArray>,
+    // only referenced through the vao, kept until it goes
+    _vbo : GlObject<NativeBuffer>,
+    _ebo : GlObject<NativeBuffer>,
 }
 
 impl UnitRect {
-    pub fn new(gl : &glow::Context, shader : &impl Shader) -> Self {
+    pub fn new(gl : &Rc<glow::Context>, shader : &impl Shader) -> Result<Self, Error> {
         let (vbo, vao, ebo) = unsafe {
             // mesh
             let vert = [
@@ -35,16 +35,16 @@ impl UnitRect {
             );
 
             // vertex arrays
-            let vao = gl.create_vertex_array().unwrap();
-            let vbo = gl.create_buffer().unwrap();
-            let ebo = gl.create_buffer().unwrap();
-            gl.bind_vertex_array(Some(vao));
-            gl.bind_buffer(glow::ARRAY_BUFFER, Some(vbo));
-            gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(ebo));
+            let vao = GlObject::create(gl, |gl| gl.create_vertex_array())?;
+            let vbo = GlObject::create(gl, |gl| gl.create_buffer())?;
+            let ebo = GlObject::create(gl, |gl| gl.create_buffer())?;
+            gl.bind_vertex_array(Some(*vao));
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(*vbo));
+            gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(*ebo));
             gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, vert, glow::STATIC_DRAW);
             gl.buffer_data_u8_slice(glow::ELEMENT_ARRAY_BUFFER, indices, glow::STATIC_DRAW);
 
-            let pos_attr = shader.get_attribute("aPos").unwrap();
+            let pos_attr = shader.get_attribute("aPos").ok_or_else(|| Error::MissingAttribute("aPos".to_string()))?;
             gl.enable_vertex_attrib_array(pos_attr);
             gl.vertex_attrib_pointer_f32(pos_attr, 3, glow::FLOAT, false, 3 * mem::size_of::<f32>() as i32, 0);
 
@@ -54,12 +54,12 @@ impl UnitRect {
             (vbo, vao, ebo) 
         };
 
-        Self { vao, vbo, ebo }
+        Ok(Self { vao, _vbo: vbo, _ebo: ebo })
     }
 
-    pub fn render(&mut self, gl : &glow::Context) {
+    pub fn render(&self, gl : &glow::Context) {
         unsafe {
-            gl.bind_vertex_array(Some(self.vao));
+            gl.bind_vertex_array(Some(*self.vao));
             gl.draw_elements(glow::TRIANGLES, 6, glow::UNSIGNED_INT, 0);
         }
     }
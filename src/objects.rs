@@ -1,3 +1,90 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{app::{camera::Camera, debug_overlay::DebugMarker, post_process::Shake, App}, audio::Audio, objects::{food_spawner::{FoodSpawner, SpawnRule}, game_mode::GameMode, game_rng::GameRng, grid::GridHighlights, particles::ParticleSystem, power_up::Effects, save_game::SaveGame, score::Score, settings::Settings, sprites::SpriteBatch}};
 
 pub mod snek;
-pub mod food;
\ No newline at end of file
+pub mod bot;
+pub mod food;
+pub mod food_spawner;
+pub mod game_mode;
+pub mod game_rng;
+pub mod grid;
+pub mod high_scores;
+pub mod hud;
+pub mod obstacles;
+pub mod particles;
+pub mod power_up;
+pub mod save_game;
+pub mod score;
+pub mod settings;
+pub mod sprites;
+
+/// state of a single run shared between the game objects, rebuilt on every restart
+#[derive(Clone)]
+pub struct GameContext {
+    pub mode: GameMode,
+    pub settings: Rc<RefCell<Settings>>,
+    pub shake: Rc<RefCell<Shake>>,
+    pub effects: Rc<RefCell<Effects>>,
+    pub spawner: Rc<RefCell<FoodSpawner>>,
+    pub score: Rc<RefCell<Score>>,
+    pub particles: Rc<RefCell<ParticleSystem>>,
+    /// filled by the food, power ups & obstacles, drawn by `Sprites`
+    pub sprites: Rc<RefCell<SpriteBatch>>,
+    pub highlights: Rc<RefCell<GridHighlights>>,
+    pub debug_markers: Rc<RefCell<Vec<DebugMarker>>>,
+    pub audio: Rc<RefCell<Audio>>,
+    /// follows the head, also tells the hud how big the window is
+    pub camera: Rc<RefCell<Camera>>,
+    pub rng: Rc<RefCell<GameRng>>,
+    /// kept up to date by the game objects, starts as the resumed run if any
+    pub save: Rc<RefCell<SaveGame>>,
+}
+
+impl GameContext {
+    /// the long lived parts come from the app, the rest starts fresh or from `resume`
+    pub fn new(app: &App, spawn_rules: Vec<SpawnRule>, resume: Option<SaveGame>) -> Self {
+        let mut save = resume.unwrap_or_default();
+        let rng = match save.snek {
+            Some(_) => GameRng::new(save.rng),
+            None => app.settings.borrow().seed.map_or_else(GameRng::from_time, GameRng::new),
+        };
+        if save.snek.is_some() {
+            // a resumed run brings its own mode, kept for the next restarts too
+            app.settings.borrow_mut().mode = save.mode;
+        }
+        let rng = Rc::new(RefCell::new(rng));
+        let score = std::mem::take(&mut save.score);
+        Self {
+            mode: app.settings.borrow().mode,
+            settings: app.settings.clone(),
+            shake: app.shake.clone(),
+            effects: Rc::new(RefCell::new(Effects::default())),
+            spawner: Rc::new(RefCell::new(FoodSpawner::new(spawn_rules, rng.clone()))),
+            score: Rc::new(RefCell::new(score)),
+            particles: Rc::new(RefCell::new(ParticleSystem::default())),
+            sprites: Rc::new(RefCell::new(SpriteBatch::default())),
+            highlights: Rc::new(RefCell::new(GridHighlights::default())),
+            debug_markers: app.debug_markers.clone(),
+            audio: app.audio.clone(),
+            camera: app.camera.clone(),
+            rng,
+            save: Rc::new(RefCell::new(save)),
+        }
+    }
+
+    /// the run as it is right now, None once it is over or before the snek moved
+    pub fn snapshot(&self) -> Option<String> {
+        let score = self.score.borrow();
+        if score.finished {
+            return None;
+        }
+        let mut save = self.save.borrow_mut();
+        // a save without the snek can't be parsed back
+        save.snek.as_ref()?;
+        save.mode = self.mode;
+        save.rng = self.rng.borrow().state();
+        save.score = score.clone();
+        Some(save.to_text())
+    }
+}
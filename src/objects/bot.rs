@@ -0,0 +1,101 @@
+use std::{cell::RefCell, rc::Rc};
+
+use rand::Rng;
+
+use crate::{
+    app::board::Position,
+    objects::{game_rng::GameRng, snek::MoveDir},
+};
+
+// chance for the random bot to turn when entering a cell, even with the way ahead clear
+const RANDOM_TURN_CHANCE: f64 = 0.15;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BotStrategy {
+    /// heads for the closest food
+    Greedy,
+    /// wanders around, turning now & then
+    Random,
+}
+
+impl BotStrategy {
+    pub const ALL: [BotStrategy; 2] = [BotStrategy::Greedy, BotStrategy::Random];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Greedy => "greedy",
+            Self::Random => "random",
+        }
+    }
+    pub fn from_arg(arg: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.name() == arg)
+    }
+}
+
+/// steers the snek instead of the player, both strategies stay clear of the edges & the body when they can <br/>
+/// draws from the run rng so a seeded bot run plays out the same every time
+pub struct Bot {
+    strategy: BotStrategy,
+    rng: Rc<RefCell<GameRng>>,
+    // a decision is made once per cell
+    cell: Option<Position>,
+}
+
+impl Bot {
+    pub fn new(strategy: BotStrategy, rng: Rc<RefCell<GameRng>>) -> Self {
+        Self { strategy, rng, cell: None }
+    }
+
+    /// the way to go from `cell`, None while still in the cell decided for or when boxed in <br/>
+    /// `is_safe` tells whether the next cell that way is free
+    pub fn decide(
+        &mut self,
+        cell: &Position,
+        dir: MoveDir,
+        food: Option<&Position>,
+        grid_size: f32,
+        is_safe: impl Fn(MoveDir) -> bool,
+    ) -> Option<MoveDir> {
+        if self.cell.as_ref() == Some(cell) {
+            return None;
+        }
+        self.cell = Some(cell.clone());
+
+        let mut safe = MoveDir::ALL
+            .into_iter()
+            .filter(|d| *d != dir.invert() && is_safe(*d))
+            .collect::<Vec<_>>();
+        if safe.is_empty() {
+            return None;
+        }
+        match self.strategy {
+            BotStrategy::Greedy => {
+                let dst = |d: &MoveDir| {
+                    let next = step(cell, *d, grid_size);
+                    food.map_or(0., |food| (next.x - food.x).abs() + (next.y - food.y).abs())
+                };
+                // ties keep the current direction, it comes first
+                safe.sort_by_key(|d| *d != dir);
+                safe.into_iter().min_by(|a, b| dst(a).total_cmp(&dst(b)))
+            }
+            BotStrategy::Random => {
+                let mut rng = self.rng.borrow_mut();
+                if safe.contains(&dir) && !rng.random_bool(RANDOM_TURN_CHANCE) {
+                    Some(dir)
+                } else {
+                    Some(safe[rng.random_range(0..safe.len())])
+                }
+            }
+        }
+    }
+}
+
+/// midpoint of the cell next to `cell` going `dir`
+pub fn step(cell: &Position, dir: MoveDir, grid_size: f32) -> Position {
+    match dir {
+        MoveDir::Up => Position { x: cell.x, y: cell.y + grid_size },
+        MoveDir::Down => Position { x: cell.x, y: cell.y - grid_size },
+        MoveDir::Right => Position { x: cell.x + grid_size, y: cell.y },
+        MoveDir::Left => Position { x: cell.x - grid_size, y: cell.y },
+    }
+}
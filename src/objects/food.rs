@@ -1,88 +1,238 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
 use rand::Rng;
 
-use crate::{app::{app_owned_data::{Collider, Setupable, Updateable}, board::Position, collider::{ColliderType, AABB}, ColliderLayer}, meshes::UnitRect, shaders::{FoodShader, Shader}};
+use crate::{app::{app_owned_data::{Collider, Setupable, Time, Updateable}, board::{Board, Position}, collider::{Circle, ColliderType}}, objects::{food_spawner::{FoodSpawner, Meal}, game_rng::GameRng, particles::{Emitter, ParticleSystem}, save_game::{FoodSave, SaveGame}, settings::Settings, sprites::SpriteBatch, GameContext}};
+
+// time a moving food takes to glide into the next cell
+const MOVE_STEP: Duration = Duration::from_millis(700);
+const EAT_PARTICLES: usize = 24;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FoodKind {
+    Normal,
+    Golden,
+    Decaying,
+    Moving,
+}
+
+impl FoodKind {
+    pub const ALL: [FoodKind; 4] = [
+        FoodKind::Normal,
+        FoodKind::Golden,
+        FoodKind::Decaying,
+        FoodKind::Moving,
+    ];
+
+    pub fn spawn_weight(&self) -> u32 {
+        match self {
+            Self::Normal => 10,
+            Self::Golden => 1,
+            Self::Decaying => 3,
+            Self::Moving => 3,
+        }
+    }
+    /// growth relative to a normal food, a decaying food starts at this worth and goes down to nothing
+    pub fn worth(&self) -> f32 {
+        match self {
+            Self::Normal => 1.,
+            Self::Golden => 3.,
+            Self::Decaying => 2.,
+            Self::Moving => 2.,
+        }
+    }
+    /// time until the food disappears on its own
+    pub fn lifetime(&self) -> Option<Duration> {
+        match self {
+            Self::Golden => Some(Duration::from_secs(6)),
+            Self::Decaying => Some(Duration::from_secs(10)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum FoodState {
+    Waiting(Duration),
+    Spawned { kind: FoodKind, age: Duration },
+}
 
 pub struct Food {
-    shader : FoodShader,
-    mesh : UnitRect,
+    sprites : Rc<RefCell<SpriteBatch>>,
+    spawner : Rc<RefCell<FoodSpawner>>,
+    particles : Rc<RefCell<ParticleSystem>>,
+    settings : Rc<RefCell<Settings>>,
+    rng : Rc<RefCell<GameRng>>,
+    save : Rc<RefCell<SaveGame>>,
+    slot : usize,
+    state : FoodState,
     radius : f32,
     position : Position,
-    collided_with_player : bool,
+    // cell a moving food glides away from
+    prev_position : Position,
 }
 
 impl Food {
-    pub fn new(gl : &glow::Context)->Food{
-        let shader = FoodShader::new(gl);
-        let mesh = UnitRect::new(gl, &shader);
+    pub fn new(slot : usize, ctx : &GameContext) -> Self {
+        // picks up where a resumed run left this slot
+        let (state, position, prev_position) = match ctx.save.borrow().foods.get(&slot) {
+            Some(food) => (food.state, food.position.clone(), food.prev_position.clone()),
+            None => (FoodState::Waiting(Duration::ZERO), Position::default(), Position::default()),
+        };
+        if let FoodState::Spawned { .. } = state {
+            ctx.spawner.borrow_mut().occupy(slot, position.clone());
+        }
 
-        Food { 
-            shader,
-            mesh,
+        Food {
+            sprites: ctx.sprites.clone(),
+            spawner: ctx.spawner.clone(),
+            particles: ctx.particles.clone(),
+            settings: ctx.settings.clone(),
+            rng: ctx.rng.clone(),
+            save: ctx.save.clone(),
+            slot,
+            state,
             radius: 0.,
-            position: Position::default(),
-            collided_with_player: true,
+            position,
+            prev_position,
         }
     }
-    fn get_new_pos(&mut self, board: &crate::app::board::Board) {
-        let mut rng = rand::rng();
-        let x = rng.random_range(0..board.midpoints.len());
-        let y = rng.random_range(0..board.midpoints[0].1.len());
-        let y = board.midpoints[x].1[y];
-        let x = board.midpoints[x].0;
-        self.position = Position{
-            x, y 
+    fn despawn(&mut self) {
+        let mut spawner = self.spawner.borrow_mut();
+        spawner.vacate(self.slot);
+        self.state = FoodState::Waiting(spawner.respawn_delay());
+    }
+    fn worth(&self) -> f32 {
+        let FoodState::Spawned { kind, age } = self.state else {
+            return 0.;
         };
+        match kind.lifetime() {
+            Some(lifetime) if kind == FoodKind::Decaying => {
+                kind.worth() * (1. - age.as_secs_f32() / lifetime.as_secs_f32()).max(0.)
+            }
+            _ => kind.worth(),
+        }
+    }
+    fn step_moving(&mut self, board: &Board) {
+        self.prev_position = self.position.clone();
+        let mut rng = self.rng.borrow_mut();
+        let mut spawner = self.spawner.borrow_mut();
+        // try the neighbouring cells in random order, stay put when boxed in
+        let mut offsets = [(1., 0.), (-1., 0.), (0., 1.), (0., -1.)];
+        for i in (1..offsets.len()).rev() {
+            offsets.swap(i, rng.random_range(0..=i));
+        }
+        for (x, y) in offsets {
+            let next = Position {
+                x: self.position.x + x * board.grid_size,
+                y: self.position.y + y * board.grid_size,
+            };
+            if board.current_midpts(next.clone()).is_some_and(|mid| mid == next) && !spawner.is_occupied(&next) {
+                spawner.occupy(self.slot, next.clone());
+                self.position = next;
+                return;
+            }
+        }
+    }
+    // where the food is drawn & collides, a moving food is in between cells
+    fn current_position(&self) -> Position {
+        let FoodState::Spawned { kind: FoodKind::Moving, age } = self.state else {
+            return self.position.clone();
+        };
+        let t = (age.as_secs_f32() / MOVE_STEP.as_secs_f32()).fract();
+        Position {
+            x: self.prev_position.x + (self.position.x - self.prev_position.x) * t,
+            y: self.prev_position.y + (self.position.y - self.prev_position.y) * t,
+        }
+    }
+    fn current_radius(&self) -> f32 {
+        match self.state {
+            FoodState::Spawned { kind: FoodKind::Decaying, .. } => {
+                self.radius * (0.4 + 0.6 * self.worth() / FoodKind::Decaying.worth())
+            }
+            _ => self.radius,
+        }
+    }
+    // spawn, age, move or expire
+    fn advance(&mut self, time : &Time, board: &Board) {
+        match self.state {
+            FoodState::Waiting(remaining) if remaining <= *time.delta => {
+                let mut spawner = self.spawner.borrow_mut();
+                let kind = spawner.pick_kind();
+                let Some(position) = spawner.place(self.slot, board) else {
+                    // every cell is taken, try again next tick
+                    self.state = FoodState::Waiting(Duration::ZERO);
+                    return;
+                };
+                self.position = position;
+                self.prev_position = self.position.clone();
+                self.state = FoodState::Spawned { kind, age: Duration::ZERO };
+            },
+            FoodState::Waiting(remaining) => {
+                self.state = FoodState::Waiting(remaining - *time.delta);
+            },
+            FoodState::Spawned { kind, age } => {
+                let new_age = age + *time.delta;
+                if kind.lifetime().is_some_and(|lifetime| new_age >= lifetime) {
+                    return self.despawn();
+                }
+                self.state = FoodState::Spawned { kind, age: new_age };
+                // moving food picks its next cell each time it arrives
+                if kind == FoodKind::Moving && (new_age.as_secs_f32() / MOVE_STEP.as_secs_f32()) as u32 != (age.as_secs_f32() / MOVE_STEP.as_secs_f32()) as u32 {
+                    self.step_moving(board);
+                }
+            },
+        }
     }
 }
 
 impl Setupable for Food {
-    fn on_setup(&mut self, gl: &glow::Context, registered_idx:usize, board: &crate::app::board::Board) {
+    fn on_setup(&mut self, _gl: &glow::Context, _registered_idx:usize, board: &crate::app::board::Board) {
         self.radius = board.grid_size/2.;
-        self.shader.use_shader(gl);
-        
-        self.get_new_pos(board);
-        
-        self.shader.set_radius(gl, self.radius);
-        self.shader.set_position(gl, self.position.x, self.position.y);
     }
 }
 
 impl Updateable for Food {
-    fn on_tick(&mut self, gl: &glow::Context, time : &crate::app::app_owned_data::Time, board: &crate::app::board::Board, _ : &mut dyn FnMut()) {
-        if self.collided_with_player {
-            self.get_new_pos(board);
-            self.collided_with_player = false;
-        }
-        self.shader.use_shader(gl);
-        self.shader.set_time(gl, (time.elapsed.as_secs_f64() * 10.).sin() as f32);
-        self.shader.set_position(gl, self.position.x, self.position.y);
-        self.mesh.render(gl);
+    fn on_tick(&mut self, _gl: &glow::Context, time : &Time, board: &Board, _ : &mut dyn FnMut()) {
+        self.advance(time, board);
+        self.save.borrow_mut().foods.insert(self.slot, FoodSave {
+            state: self.state,
+            position: self.position.clone(),
+            prev_position: self.prev_position.clone(),
+        });
+        let FoodState::Spawned { kind, .. } = self.state else {
+            return;
+        };
+
+        let phase = (time.elapsed.as_secs_f64() * 10.).sin() as f32;
+        self.sprites.borrow_mut().push_food(&self.current_position(), self.current_radius(), phase, kind);
     }
 }
 
 impl Collider for Food {
     fn check_collision(&mut self, other: &dyn Collider) {
-        if other.layer() != ColliderLayer::Player {
+        let Some(self_cldr) = self.collider().into_iter().next() else {
             return;
-        }
-        let self_cldr = &self.collider()[0];
-        let ColliderType::AABB(self_cldr) = self_cldr;
+        };
 
-        let is_intersect = other.collider().iter().any(|other| match other {
-            ColliderType::AABB(other) => self_cldr.intersects(other),
-        });
-        if is_intersect {
-            self.collided_with_player = true;
+        // only the head, the primary shape of the player, eats
+        let is_intersect = other.collider().first().is_some_and(|other| self_cldr.intersects(other));
+        if is_intersect && let FoodState::Spawned { kind, .. } = self.state {
+            let burst = Emitter::eat_burst(self.settings.borrow().theme.theme().food[kind as usize]);
+            self.particles.borrow_mut().emit(&burst, &self.current_position(), EAT_PARTICLES);
+            self.spawner.borrow_mut().eat(Meal { worth: self.worth() });
+            self.despawn();
         };
     }
     fn layer(&self) -> crate::app::ColliderLayer {
         crate::app::ColliderLayer::Food
     }
     fn collider(&self) -> Vec<crate::app::collider::ColliderType> {
-        let c = ColliderType::AABB(AABB::new(
-            Position { x: self.position.x - self.radius, y: self.position.y - self.radius }, 
-            Position { x: self.position.x + self.radius, y: self.position.y + self.radius }
-        ));
+        if let FoodState::Waiting(_) = self.state {
+            return vec![];
+        }
+        // round, like drawn
+        let c = ColliderType::Circle(Circle::new(self.current_position(), self.current_radius()));
         vec![c]
     }
-}
\ No newline at end of file
+}
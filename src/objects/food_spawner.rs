@@ -0,0 +1,195 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc, time::Duration};
+
+use rand::Rng;
+
+use crate::{
+    app::board::{Board, Position},
+    objects::{food::FoodKind, game_rng::GameRng},
+};
+
+// attempts at finding a cell that satisfies every rule before settling for any free cell
+const MAX_PLACEMENT_TRIES: usize = 32;
+
+pub enum SpawnRule {
+    /// wait before a slot is refilled after its food got eaten or expired
+    Delay(Duration),
+    /// keep new food at least this many cells away from the snek head
+    AwayFromHead(f32),
+}
+
+/// food eaten by the snek, waiting to be digested
+pub struct Meal {
+    pub worth: f32,
+}
+
+/// shared by every food slot, the obstacles & the snek <br/>
+/// decides what spawns where & collects what got eaten
+pub struct FoodSpawner {
+    rules: Vec<SpawnRule>,
+    head: Position,
+    occupied: HashMap<usize, Position>,
+    // cells taken for good, eg by obstacles
+    blocked: Vec<Position>,
+    eaten: Vec<Meal>,
+    rng: Rc<RefCell<GameRng>>,
+}
+
+impl FoodSpawner {
+    pub fn new(rules: Vec<SpawnRule>, rng: Rc<RefCell<GameRng>>) -> Self {
+        Self {
+            rules,
+            head: Position::default(),
+            occupied: HashMap::new(),
+            blocked: Vec::new(),
+            eaten: Vec::new(),
+            rng,
+        }
+    }
+
+    pub fn respawn_delay(&self) -> Duration {
+        self.rules
+            .iter()
+            .map(|rule| match rule {
+                SpawnRule::Delay(delay) => *delay,
+                _ => Duration::ZERO,
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
+    pub fn pick_kind(&self) -> FoodKind {
+        let total: u32 = FoodKind::ALL.iter().map(|k| k.spawn_weight()).sum();
+        let mut pick = self.rng.borrow_mut().random_range(0..total);
+        for kind in FoodKind::ALL {
+            if pick < kind.spawn_weight() {
+                return kind;
+            }
+            pick -= kind.spawn_weight();
+        }
+        unreachable!("pick is always below the total weight")
+    }
+
+    /// reserve a cell for the food in `slot`, None when the board has no free cell left
+    pub fn place(&mut self, slot: usize, board: &Board) -> Option<Position> {
+        self.occupied.remove(&slot);
+
+        let pos = self.find_cell(board)?;
+        self.occupied.insert(slot, pos.clone());
+        Some(pos)
+    }
+
+    /// take a cell for good, nothing spawns or moves there afterwards <br/>
+    /// None when the board has no free cell left
+    pub fn block(&mut self, board: &Board) -> Option<Position> {
+        let pos = self.find_cell(board)?;
+        self.blocked.push(pos.clone());
+        Some(pos)
+    }
+
+    /// take back a cell blocked before, eg by a resumed run
+    pub fn block_at(&mut self, pos: Position) {
+        self.blocked.push(pos);
+    }
+
+    fn find_cell(&self, board: &Board) -> Option<Position> {
+        let mut rng = self.rng.borrow_mut();
+        for _ in 0..=MAX_PLACEMENT_TRIES {
+            let pos = board.random_midpt(&mut *rng);
+            if self.is_allowed(&pos, board) {
+                return Some(pos);
+            }
+        }
+        // crowded board, go through every cell instead
+        let mut free: Vec<_> = board.midpts().filter(|pos| self.is_allowed(pos, board)).collect();
+        if free.is_empty() {
+            free = board.midpts().filter(|pos| !self.is_occupied(pos)).collect();
+        }
+        if free.is_empty() {
+            return None;
+        }
+        Some(free.swap_remove(rng.random_range(0..free.len())))
+    }
+
+    /// `slot` moved on its own, eg a moving food
+    pub fn occupy(&mut self, slot: usize, pos: Position) {
+        self.occupied.insert(slot, pos);
+    }
+
+    /// taken for good, see `block`
+    pub fn is_blocked(&self, pos: &Position) -> bool {
+        self.blocked.contains(pos)
+    }
+
+    pub fn is_occupied(&self, pos: &Position) -> bool {
+        self.occupied.values().chain(&self.blocked).any(|p| p == pos)
+    }
+
+    /// cell of the food closest to `pos`
+    pub fn nearest_food(&self, pos: &Position) -> Option<Position> {
+        let dst = |p: &&Position| (p.x - pos.x).powi(2) + (p.y - pos.y).powi(2);
+        self.occupied.values().min_by(|a, b| dst(a).total_cmp(&dst(b))).cloned()
+    }
+
+    pub fn vacate(&mut self, slot: usize) {
+        self.occupied.remove(&slot);
+    }
+
+    fn is_allowed(&self, pos: &Position, board: &Board) -> bool {
+        if self.is_occupied(pos) {
+            return false;
+        }
+        self.rules.iter().all(|rule| match rule {
+            SpawnRule::AwayFromHead(cells) => {
+                let dst = ((pos.x - self.head.x).powi(2) + (pos.y - self.head.y).powi(2)).sqrt();
+                dst >= cells * board.grid_size
+            }
+            SpawnRule::Delay(_) => true,
+        })
+    }
+
+    pub fn track_head(&mut self, head: Position) {
+        self.head = head;
+    }
+
+    pub fn eat(&mut self, meal: Meal) {
+        self.eaten.push(meal);
+    }
+
+    pub fn take_eaten(&mut self) -> Vec<Meal> {
+        std::mem::take(&mut self.eaten)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spawner(rules: Vec<SpawnRule>) -> FoodSpawner {
+        FoodSpawner::new(rules, Rc::new(RefCell::new(GameRng::new(7))))
+    }
+
+    #[test]
+    fn crowded_board_fills_every_cell_once() {
+        let board = Board::new(60, 60, 20);
+        let mut spawner = spawner(Vec::new());
+        let mut taken = Vec::new();
+        for _ in 0..9 {
+            let pos = spawner.block(&board).expect("a cell is still free");
+            assert!(!taken.contains(&pos), "{pos:?} got handed out twice");
+            taken.push(pos);
+        }
+        assert_eq!(spawner.block(&board), None);
+        assert_eq!(spawner.place(0, &board), None);
+    }
+
+    #[test]
+    fn rules_give_way_before_the_cells_run_out() {
+        let board = Board::new(40, 40, 20);
+        // no cell is that far from the head
+        let mut spawner = spawner(vec![SpawnRule::AwayFromHead(10.)]);
+        for slot in 0..4 {
+            assert!(spawner.place(slot, &board).is_some());
+        }
+        assert_eq!(spawner.place(4, &board), None);
+    }
+}
@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+const TIME_ATTACK_DURATION: Duration = Duration::from_secs(60);
+const OBSTACLE_INTERVAL: Duration = Duration::from_secs(8);
+
+/// ruleset of a run, kept by the app across restarts
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GameMode {
+    #[default]
+    Classic,
+    /// eat as much as possible before the time runs out
+    TimeAttack,
+    /// a new obstacle shows up every `OBSTACLE_INTERVAL`
+    Survival,
+    /// no death, running into the body trims the tail instead
+    Zen,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 4] = [
+        GameMode::Classic,
+        GameMode::TimeAttack,
+        GameMode::Survival,
+        GameMode::Zen,
+    ];
+
+    pub fn next(&self) -> Self {
+        let idx = Self::ALL.iter().position(|m| m == self).unwrap_or_default();
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Classic => "CLASSIC",
+            Self::TimeAttack => "TIME ATTACK",
+            Self::Survival => "SURVIVAL",
+            Self::Zen => "ZEN",
+        }
+    }
+    /// lowercase name with dashes, eg `time-attack`
+    pub fn from_arg(arg: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.name().to_lowercase().replace(' ', "-") == arg)
+    }
+    /// the run ends once this much time has been played
+    pub fn time_limit(&self) -> Option<Duration> {
+        match self {
+            Self::TimeAttack => Some(TIME_ATTACK_DURATION),
+            _ => None,
+        }
+    }
+    pub fn obstacle_interval(&self) -> Option<Duration> {
+        match self {
+            Self::Survival => Some(OBSTACLE_INTERVAL),
+            _ => None,
+        }
+    }
+    /// hitting the edges or the body ends the run
+    pub fn can_die(&self) -> bool {
+        *self != Self::Zen
+    }
+}
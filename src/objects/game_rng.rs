@@ -0,0 +1,45 @@
+use std::time::SystemTime;
+
+use rand::{rand_core::impls, RngCore};
+
+/// seedable generator behind every gameplay decision, its whole state is a single number so it can be saved <br/>
+/// cosmetic randomness, eg particles or the screen shake, keeps using `rand::rng()`
+#[derive(Clone)]
+pub struct GameRng {
+    state: u64,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+    /// a different run every time
+    pub fn from_time() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        Self::new(nanos as u64)
+    }
+    /// resuming from this state gives back the same numbers
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+}
+
+// splitmix64
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dst)
+    }
+}
@@ -0,0 +1,82 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    app::{
+        assets::Assets,
+        app_owned_data::{Setupable, Time, Updateable},
+        board::{Board, Position},
+        camera::Camera,
+    },
+    error::Error,
+    meshes::UnitRect,
+    objects::{GameContext, settings::{GridStyle, Settings}},
+    shaders::{GridShader, Shader},
+};
+
+/// cells worth pointing out while debugging the grid snapping, filled by the snek
+#[derive(Default)]
+pub struct GridHighlights {
+    pub head: Option<Position>,
+    /// where the pending turn will snap
+    pub turn: Option<Position>,
+    /// closest food to the head
+    pub food: Option<Position>,
+}
+
+/// board cells drawn under everything else
+pub struct Grid {
+    shader: Rc<GridShader>,
+    mesh: Rc<UnitRect>,
+    settings: Rc<RefCell<Settings>>,
+    highlights: Rc<RefCell<GridHighlights>>,
+    camera: Rc<RefCell<Camera>>,
+}
+
+impl Grid {
+    pub fn new(assets: &mut Assets, ctx: &GameContext) -> Result<Self, Error> {
+        let shader = assets.shader("grid", GridShader::new)?;
+        let mesh = assets.unit_rect("grid", shader.as_ref())?;
+
+        Ok(Self {
+            shader,
+            mesh,
+            settings: ctx.settings.clone(),
+            highlights: ctx.highlights.clone(),
+            camera: ctx.camera.clone(),
+        })
+    }
+}
+
+impl Setupable for Grid {
+    fn on_setup(&mut self, gl: &glow::Context, _registered_idx: usize, board: &Board) {
+        self.shader.use_shader(gl);
+        self.shader.set_grid_size(gl, board.grid_size);
+        self.shader.set_board_size(gl, board.width, board.height);
+    }
+}
+
+impl Updateable for Grid {
+    fn on_tick(&mut self, gl: &glow::Context, _time: &Time, board: &Board, _: &mut dyn FnMut()) {
+        let settings = self.settings.borrow();
+        let camera = self.camera.borrow();
+        // still drawn without a style while the view goes past the edge, to dim what is off the board
+        if settings.grid == GridStyle::Off && !settings.debug_grid && !camera.past_edge(board) {
+            return;
+        }
+
+        self.shader.use_shader(gl);
+        let (min, max) = camera.visible();
+        self.shader.set_bounds(gl, &min, &max);
+        drop(camera);
+        self.shader.set_style(gl, settings.grid);
+        let highlights = self.highlights.borrow();
+        if settings.debug_grid {
+            self.shader.set_highlights(gl, [highlights.head.as_ref(), highlights.turn.as_ref(), highlights.food.as_ref()]);
+        } else {
+            self.shader.set_highlights(gl, [None; 3]);
+        }
+        drop(highlights);
+        drop(settings);
+        self.mesh.render(gl);
+    }
+}
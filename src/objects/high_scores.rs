@@ -0,0 +1,39 @@
+use std::{fs, io::{self, Write}, path::Path, time::Duration};
+
+use crate::{objects::{game_mode::GameMode, score::Score}, records::{self, Records}};
+
+/// a finished run, one line of the scores file
+pub struct HighScore {
+    pub mode: GameMode,
+    pub points: u32,
+    pub food_eaten: u32,
+    pub elapsed: Duration,
+}
+
+/// add the run that just ended to the scores file
+pub fn record(path: impl AsRef<Path>, mode: GameMode, score: &Score) -> io::Result<()> {
+    let mode = records::index(&GameMode::ALL, &mode);
+    let mut file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{mode} {} {} {}", score.points, score.stats.food_eaten, score.elapsed.as_secs_f64())
+}
+
+/// every run kept so far, best first, no file means no runs yet <br/>
+/// no header & no keyword, every record is a run
+pub fn load(path: impl AsRef<Path>) -> Result<Vec<HighScore>, String> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.to_string()),
+    };
+    let mut scores = Vec::new();
+    for mut fields in Records::new(&text) {
+        scores.push(HighScore {
+            mode: fields.pick(&GameMode::ALL)?,
+            points: fields.next()?,
+            food_eaten: fields.next()?,
+            elapsed: fields.duration()?,
+        });
+    }
+    scores.sort_by_key(|s| std::cmp::Reverse(s.points));
+    Ok(scores)
+}
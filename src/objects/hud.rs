@@ -0,0 +1,166 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use crate::{
+    app::{
+        assets::Assets,
+        app_owned_data::{Setupable, Time, Updateable},
+        board::Board,
+        camera::Camera,
+        theme::{Color, ThemePreset},
+    },
+    audio::Volumes,
+    error::Error,
+    meshes::UnitRect,
+    objects::{GameContext, game_mode::GameMode, power_up::Effects, score::Score, settings::Settings},
+    shaders::{GLYPH_HEIGHT, Shader, TextShader, text_width},
+};
+
+const MARGIN: f32 = 8.;
+const SCALE: f32 = 3.;
+const LINE_HEIGHT: f32 = (GLYPH_HEIGHT + 2.) * SCALE;
+// how long the name of a newly picked theme & a volume change stay up
+const THEME_NAME_DURATION: Duration = Duration::from_secs(2);
+
+/// score, combo & the game over summary drawn on top of everything
+pub struct Hud {
+    shader: Rc<TextShader>,
+    mesh: Rc<UnitRect>,
+    mode: GameMode,
+    score: Rc<RefCell<Score>>,
+    effects: Rc<RefCell<Effects>>,
+    settings: Rc<RefCell<Settings>>,
+    camera: Rc<RefCell<Camera>>,
+    theme: ThemePreset,
+    // when the theme got switched, `Time::elapsed` keeps going while paused
+    theme_picked_at: Option<Duration>,
+    volumes: Volumes,
+    // what the last volume change was, with when it happened
+    volume_label: Option<(String, Duration)>,
+}
+
+impl Hud {
+    pub fn new(assets: &mut Assets, ctx: &GameContext) -> Result<Self, Error> {
+        let shader = assets.shader("text", TextShader::new)?;
+        let mesh = assets.unit_rect("text", shader.as_ref())?;
+        Ok(Self {
+            shader,
+            mesh,
+            mode: ctx.mode,
+            score: ctx.score.clone(),
+            effects: ctx.effects.clone(),
+            settings: ctx.settings.clone(),
+            camera: ctx.camera.clone(),
+            theme: ctx.settings.borrow().theme,
+            theme_picked_at: None,
+            volumes: ctx.settings.borrow().volumes,
+            volume_label: None,
+        })
+    }
+
+    fn draw(&mut self, gl: &glow::Context, text: &str, x: f32, y: f32, color: Color) {
+        self.shader.set_text(gl, text);
+        self.shader.set_origin(gl, x, y);
+        self.shader.set_color(gl, color.0, color.1, color.2);
+        self.mesh.render(gl);
+    }
+}
+
+impl Setupable for Hud {
+    fn on_setup(&mut self, gl: &glow::Context, _registered_idx: usize, _board: &Board) {
+        self.shader.use_shader(gl);
+        self.shader.set_scale(gl, SCALE);
+    }
+}
+
+impl Updateable for Hud {
+    fn on_tick(&mut self, gl: &glow::Context, time: &Time, _board: &Board, _: &mut dyn FnMut()) {
+        self.shader.use_shader(gl);
+        // laid out on the window, whatever part of the board shows
+        let (width, height) = self.camera.borrow().view_size();
+        let preset = self.settings.borrow().theme;
+        let theme = preset.theme();
+        if preset != self.theme {
+            self.theme = preset;
+            self.theme_picked_at = Some(*time.elapsed);
+        }
+        if self.theme_picked_at.is_some_and(|at| *time.elapsed - at < THEME_NAME_DURATION) {
+            let name = preset.name();
+            let x = width - MARGIN - text_width(name, SCALE);
+            self.draw(gl, name, x, MARGIN + GLYPH_HEIGHT * SCALE, theme.ui_text);
+        }
+        let volumes = self.settings.borrow().volumes;
+        if volumes != self.volumes {
+            let percent = |volume: f32| format!("{}%", (volume * 100.).round());
+            let label = if volumes.muted != self.volumes.muted {
+                if volumes.muted { "MUTED".to_string() } else { "SOUND ON".to_string() }
+            } else if volumes.master != self.volumes.master {
+                format!("MASTER {}", percent(volumes.master))
+            } else if volumes.sfx != self.volumes.sfx {
+                format!("SFX {}", percent(volumes.sfx))
+            } else {
+                format!("MUSIC {}", percent(volumes.music))
+            };
+            self.volumes = volumes;
+            self.volume_label = Some((label, *time.elapsed));
+        }
+        let label = self.volume_label.as_ref().filter(|(_, at)| *time.elapsed - *at < THEME_NAME_DURATION).map(|(label, _)| label.clone());
+        if let Some(label) = label {
+            let x = width - MARGIN - text_width(&label, SCALE);
+            // right above the theme name
+            self.draw(gl, &label, x, MARGIN + GLYPH_HEIGHT * SCALE + LINE_HEIGHT, theme.ui_text);
+        }
+        let score = self.score.borrow();
+
+        if score.finished {
+            let time_up = self.mode.time_limit().is_some_and(|limit| score.elapsed >= limit);
+            let title = if time_up { "TIME UP" } else { "GAME OVER" };
+            let mut lines = vec![title.to_string(), self.mode.name().to_string()];
+            lines.extend(score.summary_lines());
+            lines.push(String::new());
+            lines.push("PRESS ANY KEY".to_string());
+            lines.push("M TO CHANGE MODE".to_string());
+            drop(score);
+
+            let mut y = (height + lines.len() as f32 * LINE_HEIGHT) / 2.;
+            for line in lines {
+                let x = (width - text_width(&line, SCALE)) / 2.;
+                self.draw(gl, &line, x, y, theme.ui_text);
+                y -= LINE_HEIGHT;
+            }
+            return;
+        }
+
+        let points = format!("SCORE {}", score.points);
+        let combo = score.combo;
+        let combo_remaining = score.combo_remaining();
+        let time_left = self.mode.time_limit().map(|limit| limit.saturating_sub(score.elapsed));
+        drop(score);
+
+        let top = height - MARGIN;
+        self.draw(gl, &points, MARGIN, top, theme.ui_text);
+        if let Some(time_left) = time_left {
+            // warns for the last seconds
+            let text = format!("{}", time_left.as_secs_f32().ceil());
+            let color = if time_left.as_secs() < 10 { theme.ui_warning } else { theme.ui_text };
+            self.draw(gl, &text, (width - text_width(&text, SCALE)) / 2., top, color);
+        }
+        let bottom = MARGIN + GLYPH_HEIGHT * SCALE;
+        let (r, g, b) = theme.ui_text;
+        self.draw(gl, self.mode.name(), MARGIN, bottom, (r * 0.75, g * 0.75, b * 0.75));
+        if combo > 1 {
+            // fades out as the combo window runs out
+            let fade = 0.4 + 0.6 * combo_remaining;
+            let (r, g, b) = theme.ui_accent;
+            self.draw(gl, &format!("COMBO X{combo}"), MARGIN, top - LINE_HEIGHT, (r * fade, g * fade, b * fade));
+        }
+        let multiplier = self.effects.borrow().score_multiplier();
+        if multiplier > 1. {
+            // blinks so the limited time is noticed
+            let on = ((time.elapsed.as_secs_f32() * 4.) as u32).is_multiple_of(2);
+            let text = format!("X{multiplier:.0}");
+            let x = width - MARGIN - text_width(&text, SCALE);
+            let color = if on { theme.ui_accent } else { theme.ui_text };
+            self.draw(gl, &text, x, top, color);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use crate::{
+    app::{
+        ColliderLayer,
+        app_owned_data::{Collider, Setupable, Time, Updateable},
+        board::{Board, Position},
+        collider::{AABB, ColliderType},
+    },
+    objects::{GameContext, food_spawner::FoodSpawner, save_game::SaveGame, sprites::SpriteBatch},
+};
+
+/// obstacles stop dropping past that many
+pub const MAX_OBSTACLES: usize = 64;
+// a new obstacle grows in & stays harmless meanwhile, so it can't pop up on the head
+const GROW_DURATION: Duration = Duration::from_secs(1);
+
+/// blocks dropped on the board over time in survival mode
+pub struct Obstacles {
+    sprites: Rc<RefCell<SpriteBatch>>,
+    spawner: Rc<RefCell<FoodSpawner>>,
+    save: Rc<RefCell<SaveGame>>,
+    interval: Duration,
+    until_next: Duration,
+    since_last: Duration,
+    cells: Vec<Position>,
+    half_size: f32,
+}
+
+impl Obstacles {
+    pub fn new(interval: Duration, ctx: &GameContext) -> Self {
+        // a resumed run keeps its obstacles, already grown
+        let save = ctx.save.borrow();
+        let cells = save.obstacles.clone();
+        for cell in &cells {
+            ctx.spawner.borrow_mut().block_at(cell.clone());
+        }
+        let until_next = if cells.is_empty() { interval } else { save.until_next_obstacle };
+        drop(save);
+
+        Self {
+            sprites: ctx.sprites.clone(),
+            spawner: ctx.spawner.clone(),
+            save: ctx.save.clone(),
+            interval,
+            until_next,
+            since_last: GROW_DURATION,
+            cells,
+            half_size: 0.,
+        }
+    }
+}
+
+impl Setupable for Obstacles {
+    fn on_setup(&mut self, _gl: &glow::Context, _registered_idx: usize, board: &Board) {
+        self.half_size = board.grid_size / 2.;
+    }
+}
+
+impl Updateable for Obstacles {
+    fn on_tick(&mut self, _gl: &glow::Context, time: &Time, board: &Board, _: &mut dyn FnMut()) {
+        self.since_last += *time.delta;
+        if self.until_next <= *time.delta && self.cells.len() < MAX_OBSTACLES {
+            // a full board keeps the obstacle for a later tick
+            if let Some(cell) = self.spawner.borrow_mut().block(board) {
+                self.cells.push(cell);
+                self.until_next = self.interval;
+                self.since_last = Duration::ZERO;
+            }
+        } else {
+            self.until_next = self.until_next.saturating_sub(*time.delta);
+        }
+        let mut save = self.save.borrow_mut();
+        save.obstacles.clone_from(&self.cells);
+        save.until_next_obstacle = self.until_next;
+        drop(save);
+
+        // the newest one grows in
+        let grow = self.since_last.div_duration_f32(GROW_DURATION).min(1.);
+        let mut sprites = self.sprites.borrow_mut();
+        for (i, cell) in self.cells.iter().enumerate() {
+            let grow = if i == self.cells.len() - 1 { grow } else { 1. };
+            sprites.push_obstacle(cell, self.half_size * grow);
+        }
+    }
+}
+
+impl Collider for Obstacles {
+    // running into an obstacle is decided by the snek
+    fn check_collision(&mut self, _other: &dyn Collider) {}
+    fn layer(&self) -> ColliderLayer {
+        ColliderLayer::Obstacle
+    }
+    fn collider(&self) -> Vec<ColliderType> {
+        let grown = if self.since_last < GROW_DURATION {
+            self.cells.len().saturating_sub(1)
+        } else {
+            self.cells.len()
+        };
+        let half_size = self.half_size * 0.9;
+        self.cells[..grown]
+            .iter()
+            .map(|cell| {
+                ColliderType::AABB(AABB::new(
+                    Position { x: cell.x - half_size, y: cell.y - half_size },
+                    Position { x: cell.x + half_size, y: cell.y + half_size },
+                ))
+            })
+            .collect()
+    }
+}
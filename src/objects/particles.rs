@@ -0,0 +1,175 @@
+use std::{cell::RefCell, f32::consts::TAU, rc::Rc};
+
+use glow::HasContext;
+use rand::Rng;
+
+use crate::{
+    app::{
+        assets::Assets,
+        app_owned_data::{Setupable, Time, Updateable},
+        board::{Board, Position},
+        theme::Color,
+    },
+    error::Error,
+    meshes::InstancedRect,
+    objects::GameContext,
+    shaders::{ParticleShader, Shader},
+};
+
+// oldest particles are dropped past this
+const MAX_PARTICLES: usize = 4096;
+// what every particle hands to the gpu, position, size & color
+const INSTANCE_LAYOUT: [(&str, i32); 3] = [("aOffset", 2), ("aSize", 1), ("aColor", 4)];
+
+type Rgba = [f32; 4];
+
+/// how particles are spawned & how they change over their life
+#[derive(Clone)]
+pub struct Emitter {
+    /// in seconds, picked at random in between
+    pub lifetime: (f32, f32),
+    /// in pixels per second towards a random direction, picked at random in between
+    pub speed: (f32, f32),
+    /// in pixels per second², negative floats up
+    pub gravity: f32,
+    /// at birth & at death, blended over the life of a particle
+    pub color: (Rgba, Rgba),
+    /// radius at birth & at death in pixels
+    pub size: (f32, f32),
+}
+
+impl Emitter {
+    /// food getting eaten
+    pub fn eat_burst((r, g, b): Color) -> Self {
+        Self {
+            lifetime: (0.3, 0.7),
+            speed: (60., 180.),
+            gravity: 250.,
+            color: ([1., 1., 1., 1.], [r, g, b, 0.]),
+            size: (3., 1.),
+        }
+    }
+    /// the body turning into dust after dying
+    pub fn dissolve((r, g, b): Color) -> Self {
+        Self {
+            lifetime: (0.8, 1.8),
+            speed: (5., 35.),
+            gravity: -40.,
+            color: ([r, g, b, 1.], [r, g, b, 0.]),
+            size: (4., 0.5),
+        }
+    }
+    /// faint trace left by the head
+    pub fn trail((r, g, b): Color) -> Self {
+        Self {
+            lifetime: (0.3, 0.6),
+            speed: (0., 10.),
+            gravity: 0.,
+            color: ([r, g, b, 0.35], [r, g, b, 0.]),
+            size: (2.5, 0.5),
+        }
+    }
+}
+
+struct Particle {
+    position: Position,
+    velocity: (f32, f32),
+    age: f32,
+    lifetime: f32,
+    gravity: f32,
+    color: (Rgba, Rgba),
+    size: (f32, f32),
+}
+
+/// every live particle of the run, shared so any object can emit
+#[derive(Default)]
+pub struct ParticleSystem {
+    particles: Vec<Particle>,
+}
+
+impl ParticleSystem {
+    pub fn emit(&mut self, emitter: &Emitter, at: &Position, count: usize) {
+        let mut rng = rand::rng();
+        for _ in 0..count {
+            let angle = rng.random_range(0. ..TAU);
+            let speed = rng.random_range(emitter.speed.0..=emitter.speed.1);
+            self.particles.push(Particle {
+                position: at.clone(),
+                velocity: (angle.cos() * speed, angle.sin() * speed),
+                age: 0.,
+                lifetime: rng.random_range(emitter.lifetime.0..=emitter.lifetime.1),
+                gravity: emitter.gravity,
+                color: emitter.color,
+                size: emitter.size,
+            });
+        }
+        let overflow = self.particles.len().saturating_sub(MAX_PARTICLES);
+        self.particles.drain(..overflow);
+    }
+
+    fn tick(&mut self, delta: f32) {
+        for p in &mut self.particles {
+            p.age += delta;
+            p.velocity.1 -= p.gravity * delta;
+            p.position.x += p.velocity.0 * delta;
+            p.position.y += p.velocity.1 * delta;
+        }
+        self.particles.retain(|p| p.age < p.lifetime);
+    }
+
+    fn fill_instances(&self, out: &mut Vec<f32>) {
+        out.clear();
+        for p in &self.particles {
+            let t = p.age / p.lifetime;
+            let (from, to) = p.color;
+            out.extend([p.position.x, p.position.y, p.size.0 + (p.size.1 - p.size.0) * t]);
+            out.extend((0..4).map(|i| from[i] + (to[i] - from[i]) * t));
+        }
+    }
+}
+
+/// draws the particles of the run in a single instanced call
+pub struct Particles {
+    shader: Rc<ParticleShader>,
+    mesh: Rc<InstancedRect>,
+    system: Rc<RefCell<ParticleSystem>>,
+    instances: Vec<f32>,
+}
+
+impl Particles {
+    pub fn new(assets: &mut Assets, ctx: &GameContext) -> Result<Self, Error> {
+        let shader = assets.shader("particle", ParticleShader::new)?;
+        let mesh = assets.instanced_rect("particle", shader.as_ref(), &INSTANCE_LAYOUT)?;
+
+        Ok(Self {
+            shader,
+            mesh,
+            system: ctx.particles.clone(),
+            instances: Vec::new(),
+        })
+    }
+}
+
+impl Setupable for Particles {
+    fn on_setup(&mut self, _gl: &glow::Context, _registered_idx: usize, _board: &Board) {}
+}
+
+impl Updateable for Particles {
+    fn on_tick(&mut self, gl: &glow::Context, time: &Time, _board: &Board, _: &mut dyn FnMut()) {
+        let mut system = self.system.borrow_mut();
+        system.tick(time.delta.as_secs_f32());
+        system.fill_instances(&mut self.instances);
+        drop(system);
+
+        self.shader.use_shader(gl);
+        unsafe {
+            // additive, overlapping particles glow
+            gl.enable(glow::BLEND);
+            gl.blend_func(glow::SRC_ALPHA, glow::ONE);
+        }
+        self.mesh.render(gl, &self.instances);
+        unsafe {
+            gl.disable(glow::BLEND);
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use rand::Rng;
+
+use crate::{
+    app::{
+        ColliderLayer,
+        app_owned_data::{Collider, Setupable, Time, Updateable},
+        board::{Board, Position},
+        collider::{Circle, ColliderType},
+    },
+    audio::{Audio, Sfx},
+    objects::{GameContext, game_rng::GameRng, sprites::SpriteBatch},
+};
+
+const MIN_RESPAWN_DELAY: f32 = 4.;
+const MAX_RESPAWN_DELAY: f32 = 10.;
+// pickup starts blinking when it is about to disappear
+const BLINK_DURATION: Duration = Duration::from_secs(2);
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PowerUpKind {
+    SlowMotion,
+    SpeedBoost,
+    Ghost,
+    Shrink,
+    ScoreMultiplier,
+}
+
+impl PowerUpKind {
+    pub const ALL: [PowerUpKind; 5] = [
+        PowerUpKind::SlowMotion,
+        PowerUpKind::SpeedBoost,
+        PowerUpKind::Ghost,
+        PowerUpKind::Shrink,
+        PowerUpKind::ScoreMultiplier,
+    ];
+
+    /// relative chance of being picked when a pickup spawns
+    pub fn spawn_weight(&self) -> u32 {
+        match self {
+            Self::SlowMotion => 3,
+            Self::SpeedBoost => 3,
+            Self::Ghost => 2,
+            Self::Shrink => 2,
+            Self::ScoreMultiplier => 1,
+        }
+    }
+    /// how long the pickup stays on the board
+    pub fn lifetime(&self) -> Duration {
+        match self {
+            Self::ScoreMultiplier => Duration::from_secs(5),
+            _ => Duration::from_secs(8),
+        }
+    }
+    /// how long the effect lasts on the snek once picked up
+    pub fn effect_duration(&self) -> Duration {
+        match self {
+            Self::SlowMotion => Duration::from_secs(5),
+            Self::SpeedBoost => Duration::from_secs(4),
+            Self::Ghost => Duration::from_secs(5),
+            Self::Shrink => Duration::from_secs(8),
+            Self::ScoreMultiplier => Duration::from_secs(10),
+        }
+    }
+
+    fn random(rng: &mut impl Rng) -> Self {
+        let total: u32 = Self::ALL.iter().map(|k| k.spawn_weight()).sum();
+        let mut pick = rng.random_range(0..total);
+        for kind in Self::ALL {
+            if pick < kind.spawn_weight() {
+                return kind;
+            }
+            pick -= kind.spawn_weight();
+        }
+        unreachable!("pick is always below the total weight")
+    }
+}
+
+pub struct ActiveEffect {
+    pub kind: PowerUpKind,
+    pub remaining: Duration,
+}
+
+/// effects picked up by the snek, shared between the pickups & the snek
+#[derive(Default)]
+pub struct Effects(Vec<ActiveEffect>);
+
+impl Effects {
+    /// picking up an effect that is already active restarts its timer
+    pub fn apply(&mut self, kind: PowerUpKind) {
+        self.0.retain(|e| e.kind != kind);
+        self.0.push(ActiveEffect {
+            kind,
+            remaining: kind.effect_duration(),
+        });
+    }
+    pub fn tick(&mut self, delta: Duration) {
+        for effect in &mut self.0 {
+            effect.remaining = effect.remaining.saturating_sub(delta);
+        }
+        self.0.retain(|e| !e.remaining.is_zero());
+    }
+    pub fn is_active(&self, kind: PowerUpKind) -> bool {
+        self.0.iter().any(|e| e.kind == kind)
+    }
+    pub fn speed_multiplier(&self) -> f32 {
+        let mut res = 1.;
+        if self.is_active(PowerUpKind::SlowMotion) {
+            res *= 0.6;
+        }
+        if self.is_active(PowerUpKind::SpeedBoost) {
+            res *= 1.5;
+        }
+        res
+    }
+    pub fn length_multiplier(&self) -> f32 {
+        if self.is_active(PowerUpKind::Shrink) { 0.5 } else { 1. }
+    }
+    pub fn score_multiplier(&self) -> f32 {
+        if self.is_active(PowerUpKind::ScoreMultiplier) { 2. } else { 1. }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum PowerUpState {
+    Waiting(Duration),
+    Spawned { kind: PowerUpKind, remaining: Duration },
+}
+
+pub struct PowerUp {
+    sprites: Rc<RefCell<SpriteBatch>>,
+    effects: Rc<RefCell<Effects>>,
+    audio: Rc<RefCell<Audio>>,
+    rng: Rc<RefCell<GameRng>>,
+    state: PowerUpState,
+    radius: f32,
+    position: Position,
+    collided_with_player: bool,
+}
+
+impl PowerUp {
+    pub fn new(ctx: &GameContext) -> Self {
+        Self {
+            sprites: ctx.sprites.clone(),
+            effects: ctx.effects.clone(),
+            audio: ctx.audio.clone(),
+            rng: ctx.rng.clone(),
+            state: PowerUpState::Waiting(random_respawn_delay(&mut *ctx.rng.borrow_mut())),
+            radius: 0.,
+            position: Position::default(),
+            collided_with_player: false,
+        }
+    }
+}
+
+fn random_respawn_delay(rng: &mut impl Rng) -> Duration {
+    Duration::from_secs_f32(rng.random_range(MIN_RESPAWN_DELAY..MAX_RESPAWN_DELAY))
+}
+
+impl Setupable for PowerUp {
+    fn on_setup(&mut self, _gl: &glow::Context, _registered_idx: usize, board: &Board) {
+        self.radius = board.grid_size / 2.;
+    }
+}
+
+impl Updateable for PowerUp {
+    fn on_tick(&mut self, _gl: &glow::Context, time: &Time, board: &Board, _: &mut dyn FnMut()) {
+        let mut rng = self.rng.borrow_mut();
+        self.state = match self.state {
+            PowerUpState::Spawned { .. } if self.collided_with_player => {
+                self.collided_with_player = false;
+                PowerUpState::Waiting(random_respawn_delay(&mut *rng))
+            }
+            PowerUpState::Spawned { remaining, .. } if remaining <= *time.delta => {
+                PowerUpState::Waiting(random_respawn_delay(&mut *rng))
+            }
+            PowerUpState::Spawned { kind, remaining } => PowerUpState::Spawned {
+                kind,
+                remaining: remaining - *time.delta,
+            },
+            PowerUpState::Waiting(remaining) if remaining <= *time.delta => {
+                self.position = board.random_midpt(&mut *rng);
+                let kind = PowerUpKind::random(&mut *rng);
+                PowerUpState::Spawned {
+                    kind,
+                    remaining: kind.lifetime(),
+                }
+            }
+            PowerUpState::Waiting(remaining) => PowerUpState::Waiting(remaining - *time.delta),
+        };
+        drop(rng);
+
+        let PowerUpState::Spawned { kind, remaining } = self.state else {
+            return;
+        };
+        if remaining < BLINK_DURATION && ((time.elapsed.as_secs_f32() * 8.) as u32).is_multiple_of(2) {
+            return;
+        }
+        self.sprites.borrow_mut().push_power_up(&self.position, self.radius, time.elapsed.as_secs_f32(), kind);
+    }
+}
+
+impl Collider for PowerUp {
+    fn check_collision(&mut self, other: &dyn Collider) {
+        let PowerUpState::Spawned { kind, .. } = self.state else {
+            return;
+        };
+        if self.collided_with_player {
+            return;
+        }
+        let self_cldr = &self.collider()[0];
+
+        // picked up by the head, the primary shape of the player
+        let is_intersect = other.collider().first().is_some_and(|other| self_cldr.intersects(other));
+        if is_intersect {
+            self.collided_with_player = true;
+            self.effects.borrow_mut().apply(kind);
+            self.audio.borrow_mut().play(Sfx::PowerUp);
+        }
+    }
+    fn layer(&self) -> ColliderLayer {
+        ColliderLayer::PowerUp
+    }
+    fn collider(&self) -> Vec<ColliderType> {
+        if let PowerUpState::Waiting(_) = self.state {
+            return vec![];
+        }
+        let c = ColliderType::Circle(Circle::new(self.position.clone(), self.radius));
+        vec![c]
+    }
+}
@@ -0,0 +1,305 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use crate::{
+    app::board::Position,
+    objects::{
+        food::{FoodKind, FoodState},
+        game_mode::GameMode,
+        score::{RunStats, Score},
+        snek::{DirKeypoint, MoveDir},
+    },
+    records::{self, Records},
+};
+
+// first line of every save, bumped whenever the format changes
+const HEADER: &str = "snek-save 1";
+
+pub struct SnekSave {
+    pub position: Position,
+    pub dir: MoveDir,
+    pub keypoints: Vec<DirKeypoint>,
+    pub length: f32,
+    pub speed: f32,
+    pub in_edge: Duration,
+}
+
+pub struct FoodSave {
+    pub state: FoodState,
+    pub position: Position,
+    pub prev_position: Position,
+}
+
+/// everything needed to pick a run back up, power ups & particles start over <br/>
+/// the game objects keep their part up to date every tick, see `GameContext::snapshot`
+#[derive(Default)]
+pub struct SaveGame {
+    pub mode: GameMode,
+    pub rng: u64,
+    pub score: Score,
+    pub snek: Option<SnekSave>,
+    /// by slot
+    pub foods: BTreeMap<usize, FoodSave>,
+    pub obstacles: Vec<Position>,
+    pub until_next_obstacle: Duration,
+}
+
+impl SaveGame {
+    /// see `Records` for the format
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![
+            format!("mode {}", records::index(&GameMode::ALL, &self.mode)),
+            format!("rng {}", self.rng),
+        ];
+
+        let score = &self.score;
+        lines.push(format!(
+            "score {} {} {} {}",
+            score.points,
+            score.combo,
+            score.since_last_meal.as_secs_f64(),
+            score.elapsed.as_secs_f64()
+        ));
+        let stats = &score.stats;
+        lines.push(format!(
+            "stats {} {} {} {} {} {}",
+            stats.food_eaten,
+            stats.best_combo,
+            stats.turns,
+            stats.distance,
+            stats.near_misses,
+            stats.time_on_edge.as_secs_f64()
+        ));
+
+        if let Some(snek) = &self.snek {
+            lines.push(format!(
+                "snek {} {} {} {} {} {}",
+                snek.position.x,
+                snek.position.y,
+                records::index(&MoveDir::ALL, &snek.dir),
+                snek.length,
+                snek.speed,
+                snek.in_edge.as_secs_f64()
+            ));
+            for kp in &snek.keypoints {
+                let from = records::index(&MoveDir::ALL, &kp.from);
+                lines.push(format!("keypoint {from} {} {} {}", kp.at.x, kp.at.y, kp.dst_head));
+            }
+        }
+        for (slot, food) in &self.foods {
+            let state = match food.state {
+                FoodState::Waiting(remaining) => format!("waiting {}", remaining.as_secs_f64()),
+                FoodState::Spawned { kind, age } => {
+                    format!("spawned {} {}", records::index(&FoodKind::ALL, &kind), age.as_secs_f64())
+                }
+            };
+            lines.push(format!(
+                "food {slot} {} {} {} {} {state}",
+                food.position.x, food.position.y, food.prev_position.x, food.prev_position.y
+            ));
+        }
+        lines.push(format!("next-obstacle {}", self.until_next_obstacle.as_secs_f64()));
+        for cell in &self.obstacles {
+            lines.push(format!("obstacle {} {}", cell.x, cell.y));
+        }
+        records::to_text(HEADER, lines)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut save = SaveGame::default();
+        let mut keypoints = Vec::new();
+        for mut fields in Records::with_header(text, HEADER, "save")? {
+            let keyword = fields.word()?;
+            match keyword {
+                "mode" => save.mode = fields.pick(&GameMode::ALL)?,
+                "rng" => save.rng = fields.next()?,
+                "score" => {
+                    save.score.points = fields.next()?;
+                    save.score.combo = fields.next()?;
+                    save.score.since_last_meal = fields.duration()?;
+                    save.score.elapsed = fields.duration()?;
+                }
+                "stats" => {
+                    save.score.stats = RunStats {
+                        food_eaten: fields.next()?,
+                        best_combo: fields.next()?,
+                        turns: fields.next()?,
+                        distance: fields.next()?,
+                        near_misses: fields.next()?,
+                        time_on_edge: fields.duration()?,
+                    }
+                }
+                "snek" => {
+                    save.snek = Some(SnekSave {
+                        position: fields.position()?,
+                        dir: fields.pick(&MoveDir::ALL)?,
+                        keypoints: Vec::new(),
+                        length: fields.next()?,
+                        speed: fields.next()?,
+                        in_edge: fields.duration()?,
+                    })
+                }
+                "keypoint" => keypoints.push(DirKeypoint {
+                    from: fields.pick(&MoveDir::ALL)?,
+                    at: fields.position()?,
+                    dst_head: fields.next()?,
+                }),
+                "food" => {
+                    let slot = fields.next()?;
+                    let position = fields.position()?;
+                    let prev_position = fields.position()?;
+                    let state = match fields.word()? {
+                        "waiting" => FoodState::Waiting(fields.duration()?),
+                        "spawned" => FoodState::Spawned {
+                            kind: fields.pick(&FoodKind::ALL)?,
+                            age: fields.duration()?,
+                        },
+                        state => return Err(fields.error(format_args!("unknown food state `{state}`"))),
+                    };
+                    save.foods.insert(slot, FoodSave { state, position, prev_position });
+                }
+                "next-obstacle" => save.until_next_obstacle = fields.duration()?,
+                "obstacle" => save.obstacles.push(fields.position()?),
+                _ => return Err(fields.error(format_args!("unknown record `{keyword}`"))),
+            }
+        }
+        match &mut save.snek {
+            Some(snek) => snek.keypoints = keypoints,
+            None => return Err("the snek is missing".to_string()),
+        }
+        Ok(save)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(x: f32, y: f32) -> Position {
+        Position { x, y }
+    }
+
+    fn sample() -> SaveGame {
+        let mut save = SaveGame {
+            mode: GameMode::ALL[2],
+            rng: 0xDEAD_BEEF,
+            obstacles: vec![pos(12.5, 37.5), pos(62.5, 12.5)],
+            until_next_obstacle: Duration::from_millis(2500),
+            ..Default::default()
+        };
+        save.score = Score {
+            points: 130,
+            combo: 2,
+            since_last_meal: Duration::from_millis(750),
+            elapsed: Duration::from_secs(42),
+            stats: RunStats {
+                food_eaten: 9,
+                best_combo: 3,
+                turns: 17,
+                distance: 88.25,
+                near_misses: 1,
+                time_on_edge: Duration::from_millis(125),
+            },
+            finished: false,
+        };
+        save.snek = Some(SnekSave {
+            position: pos(112.5, 87.5),
+            dir: MoveDir::Left,
+            keypoints: vec![
+                DirKeypoint { from: MoveDir::Up, at: pos(137.5, 62.5), dst_head: 60. },
+                DirKeypoint { from: MoveDir::Right, at: pos(137.5, 87.5), dst_head: 25. },
+            ],
+            length: 140.,
+            speed: 129.,
+            in_edge: Duration::from_millis(250),
+        });
+        save.foods.insert(0, FoodSave {
+            state: FoodState::Waiting(Duration::from_millis(500)),
+            position: pos(0., 0.),
+            prev_position: pos(0., 0.),
+        });
+        for (slot, kind) in FoodKind::ALL.into_iter().enumerate() {
+            save.foods.insert(slot + 1, FoodSave {
+                state: FoodState::Spawned { kind, age: Duration::from_millis(100 * slot as u64) },
+                position: pos(12.5 + slot as f32 * 25., 162.5),
+                prev_position: pos(12.5, 137.5),
+            });
+        }
+        save
+    }
+
+    #[test]
+    fn round_trip_keeps_everything() {
+        let save = sample();
+        let text = save.to_text();
+        let parsed = SaveGame::parse(&text).expect("a fresh save parses");
+        assert_eq!(parsed.to_text(), text);
+
+        assert_eq!(parsed.mode, save.mode);
+        assert_eq!(parsed.rng, save.rng);
+        assert_eq!(parsed.score.points, 130);
+        assert_eq!(parsed.score.combo, 2);
+        assert_eq!(parsed.score.since_last_meal, Duration::from_millis(750));
+        assert_eq!(parsed.score.elapsed, Duration::from_secs(42));
+        let stats = &parsed.score.stats;
+        assert_eq!((stats.food_eaten, stats.best_combo, stats.turns, stats.near_misses), (9, 3, 17, 1));
+        assert_eq!(stats.distance, 88.25);
+        assert_eq!(stats.time_on_edge, Duration::from_millis(125));
+
+        let snek = parsed.snek.expect("the snek is saved");
+        assert_eq!(snek.position, pos(112.5, 87.5));
+        assert_eq!(snek.dir, MoveDir::Left);
+        assert_eq!((snek.length, snek.speed, snek.in_edge), (140., 129., Duration::from_millis(250)));
+        let keypoints: Vec<_> = snek.keypoints.iter().map(|kp| (kp.from, kp.at.clone(), kp.dst_head)).collect();
+        assert_eq!(keypoints, [(MoveDir::Up, pos(137.5, 62.5), 60.), (MoveDir::Right, pos(137.5, 87.5), 25.)]);
+
+        assert_eq!(parsed.foods.len(), FoodKind::ALL.len() + 1);
+        assert!(matches!(parsed.foods[&0].state, FoodState::Waiting(remaining) if remaining == Duration::from_millis(500)));
+        for (slot, kind) in FoodKind::ALL.into_iter().enumerate() {
+            let food = &parsed.foods[&(slot + 1)];
+            let FoodState::Spawned { kind: parsed_kind, age } = food.state else {
+                panic!("slot {} should be spawned", slot + 1);
+            };
+            assert_eq!((parsed_kind, age), (kind, Duration::from_millis(100 * slot as u64)));
+            assert_eq!(food.position, pos(12.5 + slot as f32 * 25., 162.5));
+            assert_eq!(food.prev_position, pos(12.5, 137.5));
+        }
+
+        assert_eq!(parsed.obstacles, [pos(12.5, 37.5), pos(62.5, 12.5)]);
+        assert_eq!(parsed.until_next_obstacle, Duration::from_millis(2500));
+    }
+
+    fn parse_err(text: &str) -> String {
+        match SaveGame::parse(text) {
+            Ok(_) => panic!("`{text}` shouldn't parse"),
+            Err(err) => err,
+        }
+    }
+
+    #[test]
+    fn bad_header_is_refused() {
+        let text = sample().to_text().replacen(HEADER, "snek-save 0", 1);
+        assert!(parse_err(&text).contains(HEADER));
+        assert!(parse_err("").contains(HEADER));
+    }
+
+    #[test]
+    fn unknown_record_is_refused() {
+        let text = sample().to_text() + "bonus 3\n";
+        assert!(parse_err(&text).contains("unknown record `bonus`"));
+    }
+
+    #[test]
+    fn save_without_the_snek_is_refused() {
+        let save = SaveGame { snek: None, ..sample() };
+        assert_eq!(parse_err(&save.to_text()), "the snek is missing");
+    }
+
+    #[test]
+    fn out_of_range_index_is_refused() {
+        let text = sample().to_text();
+        let mode = text.replace("mode 2", "mode 9");
+        assert!(parse_err(&mode).contains("9 is out of range"));
+        let kind = text.replace("spawned 3", "spawned 4");
+        assert!(parse_err(&kind).contains("4 is out of range"));
+    }
+}
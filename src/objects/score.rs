@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+const BASE_POINTS: f32 = 10.;
+// eating again within this window keeps the combo going
+const COMBO_WINDOW: Duration = Duration::from_secs(3);
+const COMBO_STEP: f32 = 0.5;
+const MAX_COMBO_BONUS: f32 = 4.;
+
+#[derive(Default, Clone)]
+pub struct RunStats {
+    pub food_eaten: u32,
+    pub best_combo: u32,
+    pub turns: u32,
+    /// in cells
+    pub distance: f32,
+    pub near_misses: u32,
+    pub time_on_edge: Duration,
+}
+
+/// score & statistics of the current run, shared between the snek & the hud
+#[derive(Default, Clone)]
+pub struct Score {
+    pub points: u32,
+    pub combo: u32,
+    /// for the combo window
+    pub since_last_meal: Duration,
+    /// time played, pauses excluded
+    pub elapsed: Duration,
+    pub stats: RunStats,
+    pub finished: bool,
+}
+
+impl Score {
+    /// `speed_factor` is the snek speed relative to its starting speed
+    pub fn on_meal(&mut self, worth: f32, speed_factor: f32, multiplier: f32) -> u32 {
+        if self.combo > 0 && self.since_last_meal <= COMBO_WINDOW {
+            self.combo += 1;
+        } else {
+            self.combo = 1;
+        }
+        self.since_last_meal = Duration::ZERO;
+        self.stats.food_eaten += 1;
+        self.stats.best_combo = self.stats.best_combo.max(self.combo);
+
+        let combo_bonus = (1. + (self.combo - 1) as f32 * COMBO_STEP).min(MAX_COMBO_BONUS);
+        let gained = (BASE_POINTS * worth * combo_bonus * speed_factor * multiplier).round() as u32;
+        self.points += gained;
+        gained
+    }
+
+    pub fn tick(&mut self, delta: Duration) {
+        self.elapsed += delta;
+        self.since_last_meal += delta;
+        if self.since_last_meal > COMBO_WINDOW {
+            self.combo = 0;
+        }
+    }
+
+    /// fraction of the combo window that is left, 0 when there is no combo running
+    pub fn combo_remaining(&self) -> f32 {
+        if self.combo < 2 {
+            return 0.;
+        }
+        1. - (self.since_last_meal.as_secs_f32() / COMBO_WINDOW.as_secs_f32()).min(1.)
+    }
+
+    pub fn summary_lines(&self) -> Vec<String> {
+        vec![
+            format!("SCORE {}", self.points),
+            format!("FOOD {}  BEST COMBO X{}", self.stats.food_eaten, self.stats.best_combo),
+            format!("TIME {:.0}S  TURNS {}", self.elapsed.as_secs_f32(), self.stats.turns),
+            format!("DISTANCE {:.0}", self.stats.distance),
+            format!("NEAR MISSES {}", self.stats.near_misses),
+            format!("EDGE TIME {:.1}S", self.stats.time_on_edge.as_secs_f32()),
+        ]
+    }
+}
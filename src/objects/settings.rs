@@ -0,0 +1,83 @@
+use crate::{app::{post_process::PostPass, theme::ThemePreset}, audio::Volumes, objects::{bot::BotStrategy, game_mode::GameMode}, FOOD_COUNT};
+
+/// how the board cells are shown
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GridStyle {
+    #[default]
+    Off,
+    Lines,
+    Checkerboard,
+}
+
+impl GridStyle {
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Off => Self::Lines,
+            Self::Lines => Self::Checkerboard,
+            Self::Checkerboard => Self::Off,
+        }
+    }
+}
+
+/// player choices kept by the app across restarts
+pub struct Settings {
+    /// only read when a run starts
+    pub mode: GameMode,
+    /// faint particles left behind the head
+    pub head_trail: bool,
+    pub theme: ThemePreset,
+    pub grid: GridStyle,
+    /// outline the head cell, where a pending turn snaps & the targeted food
+    pub debug_grid: bool,
+    /// collider boxes, snek turns & frame rate drawn over everything
+    pub debug_overlay: bool,
+    /// enabled post processing passes
+    pub post_passes: Vec<PostPass>,
+    /// every run starts from this seed, a different one each time otherwise
+    pub seed: Option<u64>,
+    /// starting speed of the snek, relative to the normal one
+    pub speed: f32,
+    /// food on the board at once, only read when a run starts
+    pub food_count: usize,
+    /// plays instead of the player
+    pub bot: Option<BotStrategy>,
+    /// the run is saved on quit & its score kept, off for bots & replays
+    pub persist: bool,
+    pub volumes: Volumes,
+    /// window pixels per board pixel, see `Camera`
+    pub zoom: f32,
+    /// shown while the board doesn't fit in the window
+    pub minimap: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            mode: GameMode::default(),
+            head_trail: false,
+            theme: ThemePreset::default(),
+            grid: GridStyle::default(),
+            debug_grid: false,
+            debug_overlay: false,
+            post_passes: vec![PostPass::Bloom, PostPass::Vignette],
+            seed: None,
+            speed: 1.,
+            food_count: FOOD_COUNT,
+            bot: None,
+            persist: true,
+            volumes: Volumes::default(),
+            zoom: 1.,
+            minimap: true,
+        }
+    }
+}
+
+impl Settings {
+    pub fn toggle_pass(&mut self, pass: PostPass) {
+        if self.post_passes.contains(&pass) {
+            self.post_passes.retain(|p| *p != pass);
+        } else {
+            self.post_passes.push(pass);
+        }
+    }
+}
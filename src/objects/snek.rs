@@ -1,14 +1,22 @@
 use crate::{
     app::{
-        App, ColliderLayer,
-        app_owned_data::{Collider, InputListener, Setupable, Updateable},
+        ColliderLayer,
+        assets::Assets,
+        debug_overlay::DebugMarker,
+        app_owned_data::{Collider, InputEvent, InputListener, Setupable, Updateable},
         board::{Board, Position},
-        collider::{AABB, ColliderType},
+        collider::{Capsule, Circle, ColliderType},
+        gamepad::Direction,
+        post_process::Shake,
+        camera::Camera,
     },
-    meshes,
+    audio::{Audio, MusicCue, Sfx},
+    error::Error, log, meshes,
+    objects::{GameContext, bot::{self, Bot}, food_spawner::FoodSpawner, high_scores, game_mode::GameMode, grid::GridHighlights, particles::{Emitter, ParticleSystem}, power_up::{Effects, PowerUpKind}, save_game::{SaveGame, SnekSave}, score::Score, settings::Settings},
     shaders::{Shader, SnekShader},
+    SCORES_PATH,
 };
-use std::{cmp, collections::VecDeque, time::Duration};
+use std::{cell::RefCell, collections::VecDeque, time::Duration};
 use std::rc::Rc;
 
 #[repr(u8)]
@@ -21,7 +29,10 @@ pub enum MoveDir {
 }
 
 impl MoveDir {
-    fn invert(&self) -> Self{
+    /// in `repr` order
+    pub const ALL: [MoveDir; 4] = [MoveDir::Up, MoveDir::Right, MoveDir::Down, MoveDir::Left];
+
+    pub fn invert(&self) -> Self{
         match self {
             Self::Up => Self::Down,
             Self::Right => Self::Left,
@@ -31,6 +42,15 @@ impl MoveDir {
     }
 }
 
+// why a run ended, logged with it
+#[derive(Clone, Copy, Debug)]
+enum GameOverCause {
+    Edge,
+    SelfHit,
+    Obstacle,
+    TimeUp,
+}
+
 #[derive(Clone, Debug)]
 pub struct DirKeypoint {
     pub from: MoveDir,
@@ -45,11 +65,41 @@ const INIT_SPEED: f32 = 120.;
 const MAX_SPEED:f32 = 160.;
 const SPEED_PER_FOOD: f32 = 3.;
 const MAX_DURATION_ON_EDGE:f32 = 0.1;
+// passing the body closer than this, relative to the radius, counts as a near miss
+const NEAR_MISS_MARGIN: f32 = 0.8;
+// keeps the summary from being skipped by a key that was still held when dying
+const RESTART_DELAY: Duration = Duration::from_secs(1);
+// head trail particles per second
+const TRAIL_RATE: f32 = 40.;
+// the dead body turns into this many particles every few pixels
+const DISSOLVE_SPACING: f32 = 3.;
+const DISSOLVE_DENSITY: usize = 3;
+const DEATH_SHAKE: f32 = 0.8;
+const LENGTH_PER_MUSIC_LAYER: f32 = LENGTH_PER_FOOD * 4.;
 
 pub struct Snek {
-    mesh: meshes::UnitRect,
+    mesh: Rc<meshes::UnitRect>,
     shader: Rc<SnekShader>,
+    mode: GameMode,
+    settings: Rc<RefCell<Settings>>,
+    particles: Rc<RefCell<ParticleSystem>>,
+    shake: Rc<RefCell<Shake>>,
+    highlights: Rc<RefCell<GridHighlights>>,
+    debug_markers: Rc<RefCell<Vec<DebugMarker>>>,
+    effects: Rc<RefCell<Effects>>,
+    spawner: Rc<RefCell<FoodSpawner>>,
+    score: Rc<RefCell<Score>>,
+    save: Rc<RefCell<SaveGame>>,
+    audio: Rc<RefCell<Audio>>,
+    camera: Rc<RefCell<Camera>>,
+    bot: Option<Bot>,
+    // picked up from a saved run, keeps its position on setup
+    resumed: bool,
     position: Position,
+    // where the head was before the last move, the head collider covers the whole way
+    swept_from: Position,
+    // where the head turned during the last move, the sweep bends there
+    swept_turn: Option<Position>,
     dir: MoveDir,
     dir_candidate: Option<MoveDir>,
     dir_keypoints: VecDeque<DirKeypoint>,
@@ -57,24 +107,61 @@ pub struct Snek {
     radius: f32,
     speed: f32,
     in_edge : Duration,
+    near_body : bool,
     game_over : bool,
+    dead_for : Duration,
+    restart_requested : bool,
+    // fraction of a trail particle carried over to the next tick
+    trail_debt : f32,
 }
 
 impl Snek {
-    pub fn new(app: &mut App, shader: Rc<SnekShader>) -> Self {
-        Snek {
-            mesh: meshes::UnitRect::new(&app.gl, shader.as_ref()),
+    pub fn new(assets: &mut Assets, ctx: &GameContext) -> Result<Self, Error> {
+        let shader = assets.shader("snek", SnekShader::new)?;
+        let mut snek = Snek {
+            mesh: assets.unit_rect("snek", shader.as_ref())?,
             position: Position::default(),
+            swept_from: Position::default(),
+            swept_turn: None,
             dir: MoveDir::Left,
             dir_keypoints: VecDeque::new(),
             dir_candidate: None,
             length: INIT_LENGTH,
-            speed: INIT_SPEED,
+            speed: INIT_SPEED * ctx.settings.borrow().speed,
             shader,
+            mode: ctx.mode,
+            settings: ctx.settings.clone(),
+            particles: ctx.particles.clone(),
+            shake: ctx.shake.clone(),
+            highlights: ctx.highlights.clone(),
+            debug_markers: ctx.debug_markers.clone(),
+            effects: ctx.effects.clone(),
+            spawner: ctx.spawner.clone(),
+            score: ctx.score.clone(),
+            save: ctx.save.clone(),
+            audio: ctx.audio.clone(),
+            camera: ctx.camera.clone(),
+            bot: ctx.settings.borrow().bot.map(|strategy| Bot::new(strategy, ctx.rng.clone())),
+            resumed: false,
             radius: 0.,
             in_edge : Duration::from_secs(0),
+            near_body : false,
             game_over : false,
+            dead_for : Duration::ZERO,
+            restart_requested : false,
+            trail_debt : 0.,
+        };
+        if let Some(save) = &ctx.save.borrow().snek {
+            snek.resumed = true;
+            snek.position = save.position.clone();
+            snek.swept_from = save.position.clone();
+            snek.dir = save.dir;
+            snek.dir_keypoints = save.keypoints.iter().cloned().collect();
+            snek.length = save.length;
+            snek.speed = save.speed;
+            snek.in_edge = save.in_edge;
         }
+        Ok(snek)
     }
 
     fn process_move(&mut self, board: &Board, move_dist: f32) {
@@ -126,7 +213,7 @@ impl Snek {
             changes.dst_head += move_dist
         }
 
-        let snek_length = self.length;
+        let snek_length = self.length();
         while let Some(front) = self.dir_keypoints.front()
             && front.dst_head > snek_length
         {
@@ -143,26 +230,220 @@ impl Snek {
         let kp_slice = self.dir_keypoints.as_slices();
         [kp_slice.0, kp_slice.1, &[curr_point]].concat()
     }
+    // length & speed after power up effects
+    fn length(&self) -> f32 {
+        self.length * self.effects.borrow().length_multiplier()
+    }
+    fn speed(&self) -> f32 {
+        self.speed * self.effects.borrow().speed_multiplier()
+    }
+    fn steer(&mut self, dir: MoveDir, board: &Board) {
+        // turning back into itself or straight into the edge next to it is ignored
+        let rejected = match dir {
+            _ if dir == self.dir => Some("already heading there"),
+            _ if dir == self.dir.invert() => Some("reverse"),
+            MoveDir::Up if self.position.y > board.height - board.grid_size => Some("edge"),
+            MoveDir::Left if self.position.x < board.grid_size => Some("edge"),
+            MoveDir::Right if self.position.x > board.width - board.grid_size => Some("edge"),
+            MoveDir::Down if self.position.y < board.grid_size => Some("edge"),
+            _ => None,
+        };
+        let at = format_args!("{:.1},{:.1}", self.position.x, self.position.y);
+        if let Some(reason) = rejected {
+            log::event!(Debug, "input", "turn rejected", dir = dir, heading = self.dir, reason = reason, at = at);
+            return;
+        }
+        // a turn still waiting for its cell is overridden by the latest one
+        let replaces = self.dir_candidate.replace(dir);
+        log::event!(Debug, "input", "turn queued", dir = dir, replaces = replaces, at = at);
+    }
+    // how far off a cell midpoint the head can be & still snap onto it to turn
+    fn turn_treshold(&self, board: &Board) -> f32 {
+        (GRID_TRESHOLD + ((self.speed() / INIT_SPEED)-1.) *3.).clamp(0., board.grid_size * 0.9)
+    }
+    /// midpoint where the pending turn will happen, the cell being crossed unless it is too late to snap there
+    fn pending_turn_cell(&self, board: &Board) -> Option<Position> {
+        self.dir_candidate?;
+        let midpoint = board.current_midpts(self.position.clone())?;
+        let ahead = match self.dir {
+            MoveDir::Up => midpoint.y - self.position.y,
+            MoveDir::Down => self.position.y - midpoint.y,
+            MoveDir::Right => midpoint.x - self.position.x,
+            MoveDir::Left => self.position.x - midpoint.x,
+        };
+        // a cell is only turned in once
+        let turned_here = self.dir_keypoints.back().is_some_and(|kp| board.current_midpts(kp.at.clone()).as_ref() == Some(&midpoint));
+        if ahead > -self.turn_treshold(board) && !turned_here {
+            return Some(midpoint);
+        }
+        let next = match self.dir {
+            MoveDir::Up => Position { x: midpoint.x, y: midpoint.y + board.grid_size },
+            MoveDir::Down => Position { x: midpoint.x, y: midpoint.y - board.grid_size },
+            MoveDir::Right => Position { x: midpoint.x + board.grid_size, y: midpoint.y },
+            MoveDir::Left => Position { x: midpoint.x - board.grid_size, y: midpoint.y },
+        };
+        board.current_midpts(next)
+    }
     fn on_edge(&mut self, delta : &Duration) {
-        if self.in_edge > Duration::from_secs_f32(MAX_DURATION_ON_EDGE){
-            self.game_over = true;
+        self.score.borrow_mut().stats.time_on_edge += *delta;
+        if self.in_edge.is_zero() {
+            log::event!(Debug, "game", "edge hit", heading = self.dir, at = format_args!("{:.1},{:.1}", self.position.x, self.position.y));
+        }
+        if self.in_edge > Duration::from_secs_f32(MAX_DURATION_ON_EDGE) && self.mode.can_die() {
+            self.end_run(GameOverCause::Edge);
         }else{
             self.in_edge += *delta;
         }
     }
+    fn end_run(&mut self, cause: GameOverCause) {
+        if !self.game_over {
+            let score = self.score.borrow();
+            let stats = &score.stats;
+            log::event!(
+                Info, "game", "game over",
+                cause = cause, points = score.points, elapsed = score.elapsed, food = stats.food_eaten, best_combo = stats.best_combo,
+                turns = stats.turns, distance = stats.distance, near_misses = stats.near_misses, time_on_edge = stats.time_on_edge
+            );
+            self.audio.borrow_mut().play(Sfx::Death);
+        }
+        self.game_over = true;
+    }
+    fn is_near_body(&self) -> bool {
+        let near_cldr = ColliderType::Circle(Circle::new(self.position.clone(), self.radius * (0.9 + NEAR_MISS_MARGIN)));
+        // same segments as the self collision check
+        self.collider().iter().skip(self.touching_head()).any(|cldr| cldr.intersects(&near_cldr))
+    }
+    /// whether the cell next to `cell` going `dir` is on the board & clear of the body & obstacles
+    fn is_free(&self, cell: &Position, dir: MoveDir, board: &Board) -> bool {
+        let next = bot::step(cell, dir, board.grid_size);
+        if next.x < 0. || next.x > board.width || next.y < 0. || next.y > board.height {
+            return false;
+        }
+        if self.spawner.borrow().is_blocked(&next) {
+            return false;
+        }
+        let next_cldr = ColliderType::Circle(Circle::new(next, self.radius * 0.9));
+        self.collider().iter().skip(self.head_shapes()).all(|cldr| !cldr.intersects(&next_cldr))
+    }
+    /// index in `collider()` of the first body segment the head runs into
+    fn body_hit(&self) -> Option<usize> {
+        let self_cldr = self.collider();
+        let heads = self.head_shapes();
+        self_cldr
+            .iter()
+            .enumerate()
+            .skip(self.touching_head())
+            .find_map(|(idx, cldr)| self_cldr[..heads].iter().any(|head| cldr.intersects(head)).then_some(idx))
+    }
+    // the head is swept in two parts on the frame it turned
+    fn head_shapes(&self) -> usize {
+        if self.swept_turn.is_some() { 2 } else { 1 }
+    }
+    // the head shapes & the two segments next to them always touch the head,
+    // so does the segment the turn just created, it's empty & the sweep runs along the one after it
+    fn touching_head(&self) -> usize {
+        if self.swept_turn.is_some() { 2 + 3 } else { 1 + 2 }
+    }
+    // cut the body right before the segment at `cldr_idx` so the head is free again
+    fn trim_at(&mut self, cldr_idx: usize) {
+        let keypoints = self.get_keypoints();
+        // segment k of the collider starts at the keypoint k steps away from the head
+        let segment = cldr_idx + 1 - self.head_shapes();
+        let start = &keypoints[keypoints.len() - segment];
+        let along = match start.from {
+            MoveDir::Left | MoveDir::Right => (self.position.x - start.at.x).abs(),
+            MoveDir::Up | MoveDir::Down => (self.position.y - start.at.y).abs(),
+        };
+        let length = (start.dst_head + along - self.radius * 2.).max(self.radius * 2.);
+        self.length = length / self.effects.borrow().length_multiplier();
+        self.process_dir_keypoints(0.);
+    }
+    // spread the body into particles, following the keypoints from the head to the tail
+    fn dissolve(&self) {
+        let keypoints = self.get_keypoints();
+        let length = self.length();
+        let theme = self.settings.borrow().theme.theme();
+        let mut particles = self.particles.borrow_mut();
+        for (i, kp) in keypoints.iter().enumerate().rev() {
+            let end = if i > 0 { keypoints[i - 1].dst_head.min(length) } else { length };
+            let mut dst = kp.dst_head;
+            while dst < end {
+                let along = dst - kp.dst_head;
+                let at = match kp.from {
+                    MoveDir::Up => Position { x: kp.at.x, y: kp.at.y + along },
+                    MoveDir::Down => Position { x: kp.at.x, y: kp.at.y - along },
+                    MoveDir::Left => Position { x: kp.at.x - along, y: kp.at.y },
+                    MoveDir::Right => Position { x: kp.at.x + along, y: kp.at.y },
+                };
+                // same gradient as the body
+                let color = theme.snake_at(1. - dst / length);
+                particles.emit(&Emitter::dissolve(color), &at, DISSOLVE_DENSITY);
+                dst += DISSOLVE_SPACING;
+            }
+        }
+    }
+    // corners of the box the whole body fits in, the oldest keypoint is first & leads to the tail
+    fn bounds(&self, keypoints: &[DirKeypoint]) -> (Position, Position) {
+        let tail = keypoints.first().map(|kp| {
+            let along = (self.length() - kp.dst_head).max(0.);
+            match kp.from {
+                MoveDir::Up => Position { x: kp.at.x, y: kp.at.y + along },
+                MoveDir::Down => Position { x: kp.at.x, y: kp.at.y - along },
+                MoveDir::Left => Position { x: kp.at.x - along, y: kp.at.y },
+                MoveDir::Right => Position { x: kp.at.x + along, y: kp.at.y },
+            }
+        });
+        let mut min = self.position.clone();
+        let mut max = self.position.clone();
+        for at in keypoints.iter().map(|kp| &kp.at).chain(tail.as_ref()) {
+            min = Position { x: min.x.min(at.x), y: min.y.min(at.y) };
+            max = Position { x: max.x.max(at.x), y: max.y.max(at.y) };
+        }
+        (
+            Position { x: min.x - self.radius, y: min.y - self.radius },
+            Position { x: max.x + self.radius, y: max.y + self.radius },
+        )
+    }
+    fn render(&mut self, gl: &glow::Context) {
+        let keypoints = self.get_keypoints();
+        let (min, max) = self.bounds(&keypoints);
+        self.shader.use_shader(gl);
+        self.shader.set_length(gl, self.length());
+        self.shader.set_keypoints(gl, &keypoints);
+        self.shader.set_bounds(gl, &min, &max);
+        self.mesh.render(gl);
+    }
+}
+
+// the music keeps up with the snek & gains a layer every few foods
+fn music_cue(speed: f32, length: f32, over: bool) -> MusicCue {
+    let layers = 1 + ((length - INIT_LENGTH) / LENGTH_PER_MUSIC_LAYER).max(0.) as usize;
+    MusicCue { tempo: speed / INIT_SPEED, layers, over }
+}
+
+// a bit faster with every food, up to the max scaled like the starting speed,
+// never slower than before, eg resumed with a lower `--speed`
+fn fed_speed(speed: f32, factor: f32) -> f32 {
+    (speed + SPEED_PER_FOOD).min(MAX_SPEED * factor).max(speed)
 }
 
 impl Setupable for Snek {
-    fn on_setup(&mut self, gl: &glow::Context, registered_idx: usize, board: &Board) {
-        self.position = Position {
+    fn on_setup(&mut self, gl: &glow::Context, _registered_idx: usize, board: &Board) {
+        let center = Position {
             x: board.width / 2.,
             y: board.height / 2.,
         };
+        if !self.resumed {
+            // start on a cell so turns line up with the grid
+            self.position = board.current_midpts(center.clone()).unwrap_or(center);
+            self.swept_from = self.position.clone();
+        }
         self.radius = board.grid_size / 2.;
+        self.camera.borrow_mut().follow(&self.position);
 
         self.shader.use_shader(gl);
         self.shader.set_circle_radius(gl, self.radius);
-        self.shader.set_length(gl, self.length);
+        self.shader.set_length(gl, self.length());
     }
 }
 
@@ -174,13 +455,55 @@ impl Updateable for Snek {
         board: &Board,
         game_over : &mut dyn FnMut(),
     ) {
+        self.audio.borrow_mut().cue_music(music_cue(self.speed(), self.length, self.game_over));
+        self.camera.borrow_mut().follow(&self.position);
         if self.game_over {
-            return game_over();
+            let mut score = self.score.borrow_mut();
+            if !score.finished {
+                score.finished = true;
+                if self.settings.borrow().persist
+                    && let Err(err) = high_scores::record(SCORES_PATH, self.mode, &score)
+                {
+                    log::event!(Error, "game", "can't keep the score", path = SCORES_PATH, error = err);
+                }
+                drop(score);
+                self.dissolve();
+                self.shake.borrow_mut().add(DEATH_SHAKE);
+            }
+
+            // the summary stays on screen until the player moves on, a bot moves on by itself
+            let bot_restart = self.bot.is_some() && self.dead_for >= RESTART_DELAY;
+            if self.restart_requested || bot_restart {
+                return game_over();
+            }
+            self.dead_for += *time.delta;
+            return;
         }
-        self.shader.use_shader(gl);
 
-        let move_dist = self.speed * time.delta.as_secs_f32();
+        self.effects.borrow_mut().tick(*time.delta);
+        self.score.borrow_mut().tick(*time.delta);
+        if self.mode.time_limit().is_some_and(|limit| self.score.borrow().elapsed >= limit) {
+            self.end_run(GameOverCause::TimeUp);
+            return self.render(gl);
+        }
+        for meal in self.spawner.borrow_mut().take_eaten() {
+            let speed_factor = self.speed() / INIT_SPEED;
+            let multiplier = self.effects.borrow().score_multiplier();
+            self.score.borrow_mut().on_meal(meal.worth, speed_factor, multiplier);
+            log::event!(Debug, "game", "food eaten", worth = meal.worth, points = self.score.borrow().points);
+            self.audio.borrow_mut().play(Sfx::Eat);
+
+            self.length += LENGTH_PER_FOOD * meal.worth;
+            self.speed = fed_speed(self.speed, self.settings.borrow().speed);
+        }
+        let speed = self.speed();
+
+        let move_dist = speed * time.delta.as_secs_f32();
+        self.swept_from = self.position.clone();
+        self.swept_turn = None;
         self.process_move(board, move_dist);
+        let moved = (self.position.x - self.swept_from.x).abs() + (self.position.y - self.swept_from.y).abs();
+        self.score.borrow_mut().stats.distance += moved / board.grid_size;
 
         match self.dir {
             MoveDir::Right if self.position.x == board.width - self.radius => {self.on_edge(time.delta)},
@@ -197,11 +520,22 @@ impl Updateable for Snek {
             None
         };
 
+        if let Some(mut bot) = self.bot.take() {
+            let food = self.spawner.borrow().nearest_food(&self.position);
+            let pick = bot.decide(&current_midpoint, self.dir, food.as_ref(), board.grid_size, |dir| self.is_free(&current_midpoint, dir, board));
+            match pick {
+                Some(dir) if dir == self.dir => self.dir_candidate = None,
+                Some(dir) => self.steer(dir, board),
+                None => {}
+            }
+            self.bot = Some(bot);
+        }
+
         if self.dir_candidate.is_some()
             && last_move_midpoint.is_none_or(|lm| lm != current_midpoint)
         {
             let mut adjusted_position: Option<Position> = None;
-            let treshold = (GRID_TRESHOLD + ((self.speed / INIT_SPEED)-1.) *3.).clamp(0., board.grid_size * 0.9);
+            let treshold = self.turn_treshold(board);
             //TODO: correct keypoint after correction
             let correction;
             match self.dir {
@@ -224,23 +558,70 @@ impl Updateable for Snek {
                     }
                 }
             }
-            if let Some(pos) = adjusted_position {
-                self.dir_candidate.take().map(|dir| {
-                    // process new direction fired from keyboard
-                    self.dir_keypoints.push_back(DirKeypoint {
-                        from: self.dir.clone().invert(),
-                        at: pos.clone(),
-                        dst_head: 0.0,
-                    });
-                    self.dir = dir;
-                    self.position = pos;
+            if let Some(pos) = adjusted_position
+                && let Some(dir) = self.dir_candidate.take()
+            {
+                // process new direction fired from keyboard or gamepad
+                self.dir_keypoints.push_back(DirKeypoint {
+                    from: self.dir.invert(),
+                    at: pos.clone(),
+                    dst_head: 0.0,
                 });
+                self.dir = dir;
+                self.swept_turn = Some(pos.clone());
+                self.position = pos;
+                self.score.borrow_mut().stats.turns += 1;
+                log::event!(Debug, "input", "turn taken", dir = dir, at = format_args!("{:.1},{:.1}", self.position.x, self.position.y));
+                self.audio.borrow_mut().play(Sfx::Turn);
             }
         }
 
-        self.shader.set_length(gl, self.length);
-        self.shader.set_keypoints(gl, &self.get_keypoints());
-        self.mesh.render(gl);
+        // counted once the head got away from the body it was grazing
+        let near_body = self.is_near_body();
+        if self.near_body && !near_body {
+            self.score.borrow_mut().stats.near_misses += 1;
+        }
+        self.near_body = near_body;
+
+        let settings = self.settings.borrow();
+        if settings.debug_overlay {
+            let markers = self.dir_keypoints.iter().map(|kp| DebugMarker {
+                at: kp.at.clone(),
+                label: format!("{:.0}", kp.dst_head),
+            });
+            self.debug_markers.borrow_mut().extend(markers);
+        }
+        if settings.head_trail {
+            self.trail_debt += TRAIL_RATE * time.delta.as_secs_f32();
+            let count = self.trail_debt as usize;
+            self.trail_debt -= count as f32;
+            let trail = Emitter::trail(settings.theme.theme().snake_head);
+            self.particles.borrow_mut().emit(&trail, &self.position, count);
+        }
+        drop(settings);
+
+        self.spawner.borrow_mut().track_head(self.position.clone());
+        *self.highlights.borrow_mut() = GridHighlights {
+            head: Some(current_midpoint),
+            turn: self.pending_turn_cell(board),
+            food: self.spawner.borrow().nearest_food(&self.position),
+        };
+        self.save.borrow_mut().snek = Some(SnekSave {
+            position: self.position.clone(),
+            dir: self.dir,
+            keypoints: self.dir_keypoints.iter().cloned().collect(),
+            length: self.length,
+            speed: self.speed,
+            in_edge: self.in_edge,
+        });
+        self.render(gl);
+    }
+    fn on_minimap(&mut self, gl: &glow::Context) {
+        // the body is gone once dissolved
+        if !self.game_over {
+            self.shader.use_shader(gl);
+            self.mesh.render(gl);
+        }
     }
 }
 
@@ -249,194 +630,125 @@ impl Collider for Snek {
         ColliderLayer::Player
     }
     fn check_collision(&mut self, other: &dyn Collider) {
-        if other.layer() != ColliderLayer::Food {
-            return;
-        }
-
-        let head_cldr = AABB::new(
-            Position {
-                x : self.position.x - self.radius * 0.9,
-                y : self.position.y - self.radius * 0.9,
-            },
-            Position { 
-                x : self.position.x + self.radius * 0.9,
-                y : self.position.y + self.radius * 0.9,
-            },
-        );
-        let self_cldr= self.collider();
-        let self_cldr = self_cldr.split_at_checked(2);
-        let mut head_collide_self = false;
-        match self_cldr {
-            Some((_,self_cldr)) => {
-                head_collide_self = self_cldr.iter().any(|self_cldr|match self_cldr {
-                    ColliderType::AABB(self_cldr) => {
-                        self_cldr.intersects(&head_cldr)
-                    },
-                });
-            },
-            None => {},
+        match other.layer() {
+            ColliderLayer::Obstacle => {
+                let self_cldr = self.collider();
+                let heads = &self_cldr[..self.head_shapes()];
+                let hit = other.collider().iter().any(|cldr| heads.iter().any(|head| cldr.intersects(head)));
+                if hit && self.mode.can_die() {
+                    self.end_run(GameOverCause::Obstacle);
+                }
+            }
+            // the matrix hands nothing else to the snek
+            ColliderLayer::Player | ColliderLayer::Food | ColliderLayer::PowerUp | ColliderLayer::Custom(_) => {}
         }
-        if head_collide_self {
-            self.game_over = true;
+    }
+    fn check_self_collision(&mut self) {
+        let Some(cldr_idx) = self.body_hit() else {
             return;
-        }
-
-        let head_collide = other.collider().iter().any(
-            |other_cldr| match other_cldr {
-                ColliderType::AABB(other_aabb) => head_cldr.intersects(other_aabb)});
-        if head_collide {
-            self.length += LENGTH_PER_FOOD;
-            self.speed += SPEED_PER_FOOD.clamp(0., MAX_SPEED);
         };
+        if self.effects.borrow().is_active(PowerUpKind::Ghost) {
+            return;
+        }
+        if self.mode.can_die() {
+            self.end_run(GameOverCause::SelfHit);
+        } else {
+            log::event!(Debug, "game", "tail trimmed", segment = cldr_idx);
+            self.trim_at(cldr_idx);
+        }
     }
+    /// the head comes first, followed by the body segments from the head to the tail <br/>
+    /// the head is swept from where it was before the last move, so a fast frame can't skip over anything <br/>
+    /// on the frame it turned the sweep goes up to the turn, then on from it as a second shape
     fn collider(&self) -> Vec<ColliderType> {
-        let mut remaining_len = self.length;
+        // slightly thinner than drawn so grazing isn't a hit
+        let radius = self.radius * 0.9;
+        let mut remaining_len = self.length();
         let keypoints = self.get_keypoints();
-        let mut res = Vec::with_capacity(keypoints.len());
+        let mut res = Vec::with_capacity(keypoints.len() + self.head_shapes());
+        match &self.swept_turn {
+            Some(turn) => {
+                res.push(ColliderType::Capsule(Capsule::new(self.swept_from.clone(), turn.clone(), radius)));
+                res.push(ColliderType::Capsule(Capsule::new(turn.clone(), self.position.clone(), radius)));
+            }
+            None => res.push(ColliderType::Capsule(Capsule::new(self.swept_from.clone(), self.position.clone(), radius))),
+        }
         for i in (1..keypoints.len()).rev(){
             let (n_1, n) = keypoints.split_at(i);
             let (n_1, n) = (&n_1[i-1], &n[0]);
             let len = n_1.dst_head - n.dst_head; 
             remaining_len -= len;
-
-            let (small_x, big_x) = match n.at.x.total_cmp(&n_1.at.x) {
-                cmp::Ordering::Less => (n.at.x, n_1.at.x),
-                _ => (n_1.at.x, n.at.x),
-            };
-            let (small_y, big_y) = match n.at.y.total_cmp(&n_1.at.y) {
-                cmp::Ordering::Less => (n.at.y, n_1.at.y),
-                _ => (n_1.at.y, n.at.y),
-            };
-            let aabb = AABB::new(
-                Position {
-                    x: small_x - self.radius * 0.9,
-                    y: small_y - self.radius * 0.9,
-                },
-                Position {
-                    x: big_x + self.radius * 0.9,
-                    y: big_y + self.radius * 0.9,
-                },
-            );
-            res.push(ColliderType::AABB(aabb));
+            res.push(ColliderType::Capsule(Capsule::new(n.at.clone(), n_1.at.clone(), radius)));
         };
-        let aabb = match keypoints[0].from {
-            MoveDir::Down => {
-                let small_x = keypoints[0].at.x - self.radius * 0.9;
-                let big_x = keypoints[0].at.x + self.radius * 0.9;
-                AABB::new(
-                    Position {
-                        x: small_x,
-                        y: keypoints[0].at.y - remaining_len,
-                    },
-                    Position {
-                        x: big_x,
-                        y: keypoints[0].at.y + self.radius * 0.9,
-                    },
-                )
-            },
-            MoveDir::Up => {
-                let small_x = keypoints[0].at.x - self.radius * 0.9;
-                let big_x = keypoints[0].at.x + self.radius * 0.9;
-                AABB::new(
-                    Position {
-                        x: small_x,
-                        y: keypoints[0].at.y - self.radius * 0.9,
-                    },
-                    Position {
-                        x: big_x,
-                        y: keypoints[0].at.y + remaining_len,
-                    },
-                )
-            },
-            MoveDir::Left => {
-                let small_y = keypoints[0].at.y - self.radius * 0.9;
-                let big_y = keypoints[0].at.y + self.radius * 0.9;
-                AABB::new(
-                    Position {
-                        x: keypoints[0].at.x - remaining_len,
-                        y: small_y,
-                    },
-                    Position {
-                        x: keypoints[0].at.x + self.radius * 0.9,
-                        y: big_y,
-                    },
-                )
-            },
-            MoveDir::Right => {
-                let small_y = keypoints[0].at.y - self.radius * 0.9;
-                let big_y = keypoints[0].at.y + self.radius * 0.9;
-                AABB::new(
-                    Position {
-                        x: keypoints[0].at.x - self.radius * 0.9,
-                        y: small_y,
-                    },
-                    Position {
-                        x: keypoints[0].at.x + remaining_len,
-                        y: big_y,
-                    },
-                )
-            }
+        // the tail goes on from the oldest keypoint for whatever length is left
+        let tail = &keypoints[0];
+        let remaining_len = remaining_len.max(0.);
+        let end = match tail.from {
+            MoveDir::Up => Position { x: tail.at.x, y: tail.at.y + remaining_len },
+            MoveDir::Down => Position { x: tail.at.x, y: tail.at.y - remaining_len },
+            MoveDir::Left => Position { x: tail.at.x - remaining_len, y: tail.at.y },
+            MoveDir::Right => Position { x: tail.at.x + remaining_len, y: tail.at.y },
         };
-        res.push(ColliderType::AABB(aabb));
+        res.push(ColliderType::Capsule(Capsule::new(tail.at.clone(), end, radius)));
         res
     }
 }
 
 impl InputListener for Snek {
-    fn on_input(&mut self, event: &winit::event::WindowEvent, board : &Board) {
+    fn on_input(&mut self, event: &InputEvent, board : &Board) {
         use winit::event::WindowEvent;
-        use winit::keyboard::{KeyCode, PhysicalKey};
-
-        match event {
-            WindowEvent::KeyboardInput {
-                device_id: _,
-                event,
-                is_synthetic: _,
-            } => {
-                if !event.state.is_pressed() {
-                    return;
-                }
-                match event.physical_key {
-                    PhysicalKey::Code(KeyCode::ArrowUp) | PhysicalKey::Code(KeyCode::KeyW) => {
-                        match self.dir {
-                            MoveDir::Down | MoveDir::Up => {}
-                            _ if self.position.y > board.height - board.grid_size => {}
-                            _ => {
-                                self.dir_candidate = Some(MoveDir::Up);
-                            }
-                        }
-                    }
-                    PhysicalKey::Code(KeyCode::ArrowLeft) | PhysicalKey::Code(KeyCode::KeyA) => {
-                        match self.dir {
-                            MoveDir::Right | MoveDir::Left => {}
-                            _ if self.position.x < board.grid_size => {}
-                            _ => {
-                                self.dir_candidate = Some(MoveDir::Left);
-                            }
-                        }
-                    }
-                    PhysicalKey::Code(KeyCode::ArrowRight) | PhysicalKey::Code(KeyCode::KeyD) => {
-                        match self.dir {
-                            MoveDir::Left | MoveDir::Right => {}
-                            _ if self.position.x > board.width - board.grid_size => {}
-                            _ => {
-                                self.dir_candidate = Some(MoveDir::Right);
-                            }
-                        }
-                    }
-                    PhysicalKey::Code(KeyCode::ArrowDown) | PhysicalKey::Code(KeyCode::KeyS) => {
-                        match self.dir {
-                            MoveDir::Up | MoveDir::Down => {}
-                            _ if self.position.y < board.grid_size => {}
-                            _ => {
-                                self.dir_candidate = Some(MoveDir::Down);
-                            }
-                        }
-                    }
-                    _ => {}
-                };
+
+        if self.game_over {
+            let pressed = match event {
+                InputEvent::Window(WindowEvent::KeyboardInput { event, .. }) => event.state.is_pressed(),
+                InputEvent::Gamepad(_) => true,
+                _ => false,
+            };
+            if pressed && self.dead_for >= RESTART_DELAY {
+                self.restart_requested = true;
+            } else if let Some(dir) = event.direction() {
+                log::event!(Debug, "input", "turn ignored", dir = dir, reason = "game over");
             }
-            _ => {}
+            return;
         }
+
+        let dir = match event.direction() {
+            Some(Direction::Up) => MoveDir::Up,
+            Some(Direction::Left) => MoveDir::Left,
+            Some(Direction::Right) => MoveDir::Right,
+            Some(Direction::Down) => MoveDir::Down,
+            None => return,
+        };
+        self.steer(dir, board);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn music_follows_speed_and_length() {
+        assert_eq!(music_cue(INIT_SPEED, INIT_LENGTH, false), MusicCue::default());
+        assert!(music_cue(MAX_SPEED, INIT_LENGTH, false).tempo > music_cue(INIT_SPEED, INIT_LENGTH, false).tempo);
+
+        let layers = |foods: f32| music_cue(INIT_SPEED, INIT_LENGTH + foods * LENGTH_PER_FOOD, false).layers;
+        assert_eq!(layers(3.), 1);
+        assert_eq!(layers(4.), 2);
+        assert_eq!(layers(12.), 4);
+        // shorter than at the start, eg shrunk by a power up
+        assert_eq!(music_cue(INIT_SPEED, INIT_LENGTH / 2., false).layers, 1);
+        assert!(music_cue(INIT_SPEED, INIT_LENGTH, true).over);
+    }
+
+    #[test]
+    fn food_speeds_up_to_the_scaled_max() {
+        assert_eq!(fed_speed(INIT_SPEED, 1.), INIT_SPEED + SPEED_PER_FOOD);
+        assert_eq!(fed_speed(MAX_SPEED, 1.), MAX_SPEED);
+        // `--speed 2` starts above the normal max & keeps speeding up
+        assert_eq!(fed_speed(INIT_SPEED * 2., 2.), INIT_SPEED * 2. + SPEED_PER_FOOD);
+        assert_eq!(fed_speed(MAX_SPEED * 2., 2.), MAX_SPEED * 2.);
+        // a run resumed with a lower factor keeps its speed
+        assert_eq!(fed_speed(INIT_SPEED * 2., 1.), INIT_SPEED * 2.);
     }
 }
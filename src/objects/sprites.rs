@@ -0,0 +1,108 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    app::{
+        assets::Assets,
+        app_owned_data::{Setupable, Time, Updateable},
+        board::{Board, Position},
+    },
+    error::Error,
+    meshes::InstancedRect,
+    objects::{GameContext, food::FoodKind, power_up::PowerUpKind},
+    shaders::{FoodShader, ObstacleShader, PowerUpShader, Shader},
+};
+
+// kinds are passed as floats, vertex attributes are all floats here
+const FOOD_LAYOUT: [(&str, i32); 4] = [("aCenter", 2), ("aRadius", 1), ("aPhase", 1), ("aKind", 1)];
+const POWER_UP_LAYOUT: [(&str, i32); 4] = [("aCenter", 2), ("aRadius", 1), ("aTime", 1), ("aKind", 1)];
+const OBSTACLE_LAYOUT: [(&str, i32); 2] = [("aCenter", 2), ("aHalfSize", 1)];
+
+/// what the food, power ups & obstacles want drawn this tick, emptied once `Sprites` drew it
+#[derive(Default, Clone)]
+pub struct SpriteBatch {
+    food: Vec<f32>,
+    power_ups: Vec<f32>,
+    obstacles: Vec<f32>,
+}
+
+impl SpriteBatch {
+    /// `phase` goes from -1 to 1, the food lightens & shrinks towards 1
+    pub fn push_food(&mut self, center: &Position, radius: f32, phase: f32, kind: FoodKind) {
+        self.food.extend([center.x, center.y, radius, phase, kind as u32 as f32]);
+    }
+    /// `time` in seconds drives the pulse of some kinds
+    pub fn push_power_up(&mut self, center: &Position, radius: f32, time: f32, kind: PowerUpKind) {
+        self.power_ups.extend([center.x, center.y, radius, time, kind as u32 as f32]);
+    }
+    pub fn push_obstacle(&mut self, center: &Position, half_size: f32) {
+        self.obstacles.extend([center.x, center.y, half_size]);
+    }
+}
+
+/// draws every food, power up & obstacle of the tick, a single instanced call per kind <br/>
+/// taken after the objects filling the batch, so they are all in by the time it ticks
+pub struct Sprites {
+    food_shader: Rc<FoodShader>,
+    food_mesh: Rc<InstancedRect>,
+    power_up_shader: Rc<PowerUpShader>,
+    power_up_mesh: Rc<InstancedRect>,
+    obstacle_shader: Rc<ObstacleShader>,
+    obstacle_mesh: Rc<InstancedRect>,
+    batch: Rc<RefCell<SpriteBatch>>,
+    // the tick's batch, drawn again on the minimap
+    shown: SpriteBatch,
+}
+
+impl Sprites {
+    pub fn new(assets: &mut Assets, ctx: &GameContext) -> Result<Self, Error> {
+        let food_shader = assets.shader("food", FoodShader::new)?;
+        let food_mesh = assets.instanced_rect("food", food_shader.as_ref(), &FOOD_LAYOUT)?;
+        let power_up_shader = assets.shader("power_up", PowerUpShader::new)?;
+        let power_up_mesh = assets.instanced_rect("power_up", power_up_shader.as_ref(), &POWER_UP_LAYOUT)?;
+        let obstacle_shader = assets.shader("obstacle", ObstacleShader::new)?;
+        let obstacle_mesh = assets.instanced_rect("obstacle", obstacle_shader.as_ref(), &OBSTACLE_LAYOUT)?;
+
+        Ok(Self {
+            food_shader,
+            food_mesh,
+            power_up_shader,
+            power_up_mesh,
+            obstacle_shader,
+            obstacle_mesh,
+            batch: ctx.sprites.clone(),
+            shown: SpriteBatch::default(),
+        })
+    }
+}
+
+impl Setupable for Sprites {
+    fn on_setup(&mut self, _gl: &glow::Context, _registered_idx: usize, _board: &Board) {}
+}
+
+impl Sprites {
+    fn draw(&self, gl: &glow::Context, batch: &SpriteBatch) {
+        // same order they were drawn in one by one
+        if !batch.food.is_empty() {
+            self.food_shader.use_shader(gl);
+            self.food_mesh.render(gl, &batch.food);
+        }
+        if !batch.power_ups.is_empty() {
+            self.power_up_shader.use_shader(gl);
+            self.power_up_mesh.render(gl, &batch.power_ups);
+        }
+        if !batch.obstacles.is_empty() {
+            self.obstacle_shader.use_shader(gl);
+            self.obstacle_mesh.render(gl, &batch.obstacles);
+        }
+    }
+}
+
+impl Updateable for Sprites {
+    fn on_tick(&mut self, gl: &glow::Context, _time: &Time, _board: &Board, _: &mut dyn FnMut()) {
+        self.shown = std::mem::take(&mut *self.batch.borrow_mut());
+        self.draw(gl, &self.shown);
+    }
+    fn on_minimap(&mut self, gl: &glow::Context) {
+        self.draw(gl, &self.shown);
+    }
+}
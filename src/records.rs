@@ -0,0 +1,135 @@
+use std::{fmt::Display, iter::Enumerate, str::{FromStr, Lines, SplitWhitespace}, time::Duration};
+
+use crate::app::board::Position;
+
+/// the text files of the game, saves, recordings & high scores <br/>
+/// one record per line, a keyword followed by its values, blank lines are skipped
+pub struct Records<'a> {
+    lines: Enumerate<Lines<'a>>,
+}
+
+impl<'a> Records<'a> {
+    /// every line is a record
+    pub fn new(text: &'a str) -> Self {
+        Self { lines: text.lines().enumerate() }
+    }
+    /// the first line has to be `header`, `what` the file is goes in the error
+    pub fn with_header(text: &'a str, header: &str, what: &str) -> Result<Self, String> {
+        let mut records = Self::new(text);
+        if records.lines.next().map(|(_, l)| l.trim()) != Some(header) {
+            return Err(format!("not a {what}, expected `{header}` on the first line"));
+        }
+        Ok(records)
+    }
+}
+
+impl<'a> Iterator for Records<'a> {
+    type Item = Fields<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.lines
+            .by_ref()
+            .map(|(idx, line)| Fields { line: idx + 1, values: line.split_whitespace() })
+            .find(|fields| fields.values.clone().next().is_some())
+    }
+}
+
+/// values of a single record, read in order
+pub struct Fields<'a> {
+    line: usize,
+    values: SplitWhitespace<'a>,
+}
+
+impl<'a> Fields<'a> {
+    pub fn word(&mut self) -> Result<&'a str, String> {
+        self.values.next().ok_or_else(|| self.error("missing value"))
+    }
+    pub fn next<T: FromStr>(&mut self) -> Result<T, String> {
+        self.parse_with(|value| value.parse().ok())
+    }
+    pub fn parse_with<T>(&mut self, parse: impl FnOnce(&str) -> Option<T>) -> Result<T, String> {
+        let value = self.word()?;
+        parse(value).ok_or_else(|| self.error(format_args!("can't read `{value}`")))
+    }
+    /// whatever is left on the line
+    pub fn rest<T>(&mut self, parse: impl Fn(&str) -> Option<T>) -> Result<Vec<T>, String> {
+        let mut res = Vec::new();
+        while self.values.clone().next().is_some() {
+            res.push(self.parse_with(&parse)?);
+        }
+        Ok(res)
+    }
+    /// in seconds
+    pub fn duration(&mut self) -> Result<Duration, String> {
+        let secs: f64 = self.next()?;
+        Duration::try_from_secs_f64(secs).map_err(|_| self.error(format_args!("bad duration {secs}")))
+    }
+    pub fn position(&mut self) -> Result<Position, String> {
+        Ok(Position { x: self.next()?, y: self.next()? })
+    }
+    /// one of `all`, written with `index`
+    pub fn pick<T: Copy>(&mut self, all: &[T]) -> Result<T, String> {
+        let idx: usize = self.next()?;
+        all.get(idx).copied().ok_or_else(|| self.error(format_args!("{idx} is out of range")))
+    }
+    /// tells which line went wrong
+    pub fn error(&self, msg: impl Display) -> String {
+        format!("line {}: {msg}", self.line)
+    }
+}
+
+/// `value` as written in a record, see `Fields::pick`
+pub fn index<T: PartialEq>(all: &[T], value: &T) -> usize {
+    all.iter().position(|v| v == value).unwrap_or_default()
+}
+
+/// `header` on the first line, then every record
+pub fn to_text(header: &str, records: Vec<String>) -> String {
+    let mut text = String::from(header) + "\n";
+    for record in records {
+        text += &record;
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_is_checked() {
+        assert!(Records::with_header("snek 1\n", "snek 1", "test").is_ok());
+        let Err(err) = Records::with_header("snek 2\n", "snek 1", "test") else {
+            panic!("wrong header accepted");
+        };
+        assert_eq!(err, "not a test, expected `snek 1` on the first line");
+    }
+
+    #[test]
+    fn fields_are_read_in_order_with_their_line() {
+        let text = to_text("snek 1", vec!["pos 1.5 2 7".into(), String::new(), "dirs 0 1 x".into()]);
+        let mut records = Records::with_header(&text, "snek 1", "test").unwrap();
+
+        let mut pos = records.next().unwrap();
+        assert_eq!(pos.word(), Ok("pos"));
+        assert_eq!(pos.position(), Ok(Position { x: 1.5, y: 2. }));
+        assert_eq!(pos.duration(), Ok(Duration::from_secs(7)));
+        assert_eq!(pos.word(), Err("line 2: missing value".to_string()));
+
+        // the blank line is skipped but still counted
+        let mut dirs = records.next().unwrap();
+        assert_eq!(dirs.word(), Ok("dirs"));
+        assert_eq!(dirs.pick(&["up", "down"]), Ok("up"));
+        assert_eq!(dirs.next::<u8>(), Ok(1));
+        assert_eq!(dirs.next::<u8>(), Err("line 4: can't read `x`".to_string()));
+        assert!(records.next().is_none());
+    }
+
+    #[test]
+    fn picks_stay_in_range() {
+        let mut fields = Records::new("2").next().unwrap();
+        assert_eq!(fields.pick(&[0, 1]), Err("line 1: 2 is out of range".to_string()));
+        assert_eq!(index(&['a', 'b'], &'b'), 1);
+    }
+}
@@ -1,30 +1,76 @@
-use glow::{HasContext, NativeProgram};
+use std::{fs, path::Path, rc::Rc};
+
+use glow::{HasContext, NativeProgram, NativeUniformLocation};
+
+use crate::{app::{camera::CAMERA_BINDING, theme::THEME_BINDING}, error::Error, gl_object::GlObject, log};
 
 mod snek;
 mod food;
+mod power_up;
+mod obstacle;
+mod grid;
+mod particle;
+mod debug;
+mod post;
+mod text;
 
 pub use snek::SnekShader;
 pub use food::FoodShader;
+pub use power_up::PowerUpShader;
+pub use obstacle::ObstacleShader;
+pub use grid::GridShader;
+pub use particle::ParticleShader;
+pub use debug::DebugShader;
+pub use post::PostShader;
+pub use text::{TextShader, text_width, GLYPH_HEIGHT};
 
 pub trait Shader {
     fn use_shader(&self, gl: &glow::Context);
     fn get_attribute(&self, key : &str)->Option<u32>;
 }
 
-fn gen_program(gl: &glow::Context, vs_str :&str, fs_str: &str) -> Result<NativeProgram, String> {
+// shaders are looked up from the working directory
+const SHADER_DIR: &str = "./shader";
+
+fn read_shader(file: &str) -> Result<String, Error> {
+    let path = Path::new(SHADER_DIR).join(file);
+    fs::read_to_string(&path).map_err(|source| Error::ShaderFile { path, source })
+}
+
+/// `vs` & `fs` file names in the shader directory, `name` shows up in the logs & errors
+fn load_program(gl: &Rc<glow::Context>, name: &str, vs: &str, fs: &str) -> Result<GlObject<NativeProgram>, Error> {
+    gen_program(gl, name, &read_shader(vs)?, &read_shader(fs)?)
+}
+
+fn uniform(gl: &glow::Context, program: NativeProgram, shader: &str, uniform: &str) -> Result<NativeUniformLocation, Error> {
+    unsafe { gl.get_uniform_location(program, uniform) }
+        .ok_or_else(|| Error::MissingUniform { shader: shader.to_string(), uniform: uniform.to_string() })
+}
+
+fn gen_program(gl: &Rc<glow::Context>, name: &str, vs_str :&str, fs_str: &str) -> Result<GlObject<NativeProgram>, Error> {
+    let _span = log::span!(Debug, "shader", "compile", name = name);
+    // every stage gets the camera block & the fragment stage the theme block, right after the version line
+    let camera = read_shader("camera.glsl")?;
+    let theme = read_shader("theme.glsl")?;
+    let vs_str = with_header(vs_str, &camera);
+    let fs_str = with_header(fs_str, &format!("{camera}{theme}"));
+    let (vs_str, fs_str) = (vs_str.as_str(), fs_str.as_str());
     let shader_srcs = [
-        (glow::VERTEX_SHADER, &vs_str),
-        (glow::FRAGMENT_SHADER, &fs_str),
+        (glow::VERTEX_SHADER, "vertex", &vs_str),
+        (glow::FRAGMENT_SHADER, "fragment", &fs_str),
     ]; 
 
     let shaders = unsafe {
         let mut res = Vec::with_capacity(shader_srcs.len()); 
-        for (kind, src) in shader_srcs {
-            let shader = gl.create_shader(kind)?;
-            gl.shader_source(shader, src);
-            gl.compile_shader(shader);
-            if !gl.get_shader_compile_status(shader) {
-                return Err(gl.get_shader_info_log(shader));
+        for (kind, stage, src) in shader_srcs {
+            // deleted on the way out, failed or not
+            let shader = GlObject::create(gl, |gl| gl.create_shader(kind))?;
+            gl.shader_source(*shader, src);
+            gl.compile_shader(*shader);
+            if !gl.get_shader_compile_status(*shader) {
+                let log = gl.get_shader_info_log(*shader);
+                log::event!(Debug, "shader", "compile failed", stage = stage, info_log = log);
+                return Err(Error::ShaderCompile { name: name.to_string(), stage, log });
             };
             res.push(shader);
         }
@@ -32,21 +78,35 @@ fn gen_program(gl: &glow::Context, vs_str :&str, fs_str: &str) -> Result<NativeP
     };
 
     let program = unsafe {
-        let program = gl.create_program()?;
+        let program = GlObject::create(gl, |gl| gl.create_program())?;
         for shader in &shaders {
-            gl.attach_shader(program, *shader);
+            gl.attach_shader(*program, **shader);
         };
         
-        gl.link_program(program);
-        if !gl.get_program_link_status(program) {
-            return Err(gl.get_program_info_log(program));
+        gl.link_program(*program);
+        if !gl.get_program_link_status(*program) {
+            let log = gl.get_program_info_log(*program);
+            log::event!(Debug, "shader", "link failed", info_log = log);
+            return Err(Error::ShaderLink { name: name.to_string(), log });
         }
         for shader in &shaders {
-            gl.detach_shader(program, *shader);
-            gl.delete_shader(*shader);
+            gl.detach_shader(*program, **shader);
         };
+        if let Some(block) = gl.get_uniform_block_index(*program, "Theme") {
+            gl.uniform_block_binding(*program, block, THEME_BINDING);
+        }
+        if let Some(block) = gl.get_uniform_block_index(*program, "Camera") {
+            gl.uniform_block_binding(*program, block, CAMERA_BINDING);
+        }
         program
     };
 
     Ok(program)
-}
\ No newline at end of file
+}
+
+fn with_header(src: &str, header: &str) -> String {
+    match src.split_once('\n') {
+        Some((version, rest)) => format!("{version}\n{header}{rest}"),
+        None => src.to_string(),
+    }
+}
@@ -0,0 +1,39 @@
+use std::{collections::HashMap, rc::Rc};
+
+use glow::{HasContext, NativeProgram};
+
+use crate::{error::Error, gl_object::GlObject, shaders::{load_program, Shader}};
+
+pub struct DebugShader {
+    program: GlObject<NativeProgram>,
+    attributes: HashMap<String, u32>,
+}
+
+impl DebugShader {
+    pub fn new(gl: &Rc<glow::Context>) -> Result<Self, Error> {
+        let attributes = [
+            ("aPos".to_string(), 0),
+            ("aStart".to_string(), 1),
+            ("aEnd".to_string(), 2),
+            ("aRadius".to_string(), 3),
+            ("aColor".to_string(), 4),
+        ];
+        let program = load_program(gl, "debug", "debug.vs", "debug.fs")?;
+
+        Ok(Self {
+            program,
+            attributes: HashMap::from(attributes),
+        })
+    }
+}
+
+impl Shader for DebugShader {
+    fn get_attribute(&self, key: &str) -> Option<u32> {
+        self.attributes.get(key).copied()
+    }
+    fn use_shader(&self, gl: &glow::Context) {
+        unsafe {
+            gl.use_program(Some(*self.program));
+        }
+    }
+}
@@ -1,57 +1,30 @@
-use std::{collections::HashMap, fs};
+use std::{collections::HashMap, rc::Rc};
 
-use glow::{HasContext, NativeProgram, NativeUniformLocation};
+use glow::{HasContext, NativeProgram};
 
-use crate::shaders::{gen_program, Shader};
+use crate::{error::Error, gl_object::GlObject, shaders::{load_program, Shader}};
 
+/// one instance per food, see `shader/food.vs` for its attributes
 pub struct FoodShader {
-    program: NativeProgram,
-    u_radius: NativeUniformLocation,
-    u_position: NativeUniformLocation,
-    u_time: NativeUniformLocation,
+    program: GlObject<NativeProgram>,
     attributes: HashMap<String, u32>,
 }
 
 impl FoodShader {
-    pub fn new(gl: &glow::Context) -> Self {
-        let attributes = [("aPos".to_string(), 0)];
-        let (vs, fs) = {
-            let vs = fs::read_to_string("./shader/.vs").expect("can't load vertex shader");
-            let fs = fs::read_to_string("./shader/food.fs").expect("can't load fragment shader");
-            (vs, fs)
-        };
-        let program = gen_program(gl, &vs, &fs).unwrap();
+    pub fn new(gl: &Rc<glow::Context>) -> Result<Self, Error> {
+        let attributes = [
+            ("aPos".to_string(), 0),
+            ("aCenter".to_string(), 1),
+            ("aRadius".to_string(), 2),
+            ("aPhase".to_string(), 3),
+            ("aKind".to_string(), 4),
+        ];
+        let program = load_program(gl, "food", "food.vs", "food.fs")?;
 
-        let (u_radius, u_position, u_time) = unsafe {
-            (
-                gl.get_uniform_location(program, "uRadius").unwrap(),
-                gl.get_uniform_location(program, "uPosition").unwrap(),
-                gl.get_uniform_location(program, "uTime").unwrap(),
-            )
-        };
-
-        Self {
+        Ok(Self {
             program,
-            u_radius,
-            u_position,
-            u_time,
             attributes: HashMap::from(attributes),
-        }
-    }
-    pub fn set_position(&self, gl: &glow::Context, x: f32, y: f32) {
-        unsafe {
-            gl.uniform_2_f32(Some(&self.u_position), x, y);
-        }
-    }
-    pub fn set_time(&self, gl: &glow::Context, x: f32) {
-        unsafe {
-            gl.uniform_1_f32(Some(&self.u_time), x);
-        }
-    }
-    pub fn set_radius(&self, gl: &glow::Context, x: f32) {
-        unsafe {
-            gl.uniform_1_f32(Some(&self.u_radius), x);
-        }
+        })
     }
 }
 
@@ -61,7 +34,7 @@ impl Shader for FoodShader {
     }
     fn use_shader(&self, gl: &glow::Context) {
         unsafe {
-            gl.use_program(Some(self.program));
+            gl.use_program(Some(*self.program));
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,91 @@
+use std::{collections::HashMap, rc::Rc};
+
+use glow::{HasContext, NativeProgram, NativeUniformLocation};
+
+use crate::{app::board::Position, error::Error, gl_object::GlObject, objects::settings::GridStyle, shaders::{load_program, uniform, Shader}};
+
+pub struct GridShader {
+    program: GlObject<NativeProgram>,
+    u_grid_size: NativeUniformLocation,
+    u_board_size: NativeUniformLocation,
+    u_bounds: NativeUniformLocation,
+    u_style: NativeUniformLocation,
+    u_highlights: [NativeUniformLocation; 3],
+    u_highlight_mask: NativeUniformLocation,
+    attributes: HashMap<String, u32>,
+}
+
+impl GridShader {
+    pub fn new(gl: &Rc<glow::Context>) -> Result<Self, Error> {
+        let attributes = [("aPos".to_string(), 0)];
+        let program = load_program(gl, "grid", "quad.vs", "grid.fs")?;
+
+        let (u_grid_size, u_board_size, u_bounds, u_style, u_highlight_mask) = (
+            uniform(gl, *program, "grid", "uGridSize")?,
+            uniform(gl, *program, "grid", "uBoardSize")?,
+            uniform(gl, *program, "grid", "uBounds")?,
+            uniform(gl, *program, "grid", "uStyle")?,
+            uniform(gl, *program, "grid", "uHighlightMask")?,
+        );
+        let u_highlights = array_init::try_array_init(|i| uniform(gl, *program, "grid", &format!("uHighlights[{i}]")))?;
+
+        Ok(Self {
+            program,
+            u_grid_size,
+            u_board_size,
+            u_bounds,
+            u_style,
+            u_highlights,
+            u_highlight_mask,
+            attributes: HashMap::from(attributes),
+        })
+    }
+    pub fn set_grid_size(&self, gl: &glow::Context, x: f32) {
+        unsafe {
+            gl.uniform_1_f32(Some(&self.u_grid_size), x);
+        }
+    }
+    /// past it is drawn as off the board
+    pub fn set_board_size(&self, gl: &glow::Context, width: f32, height: f32) {
+        unsafe {
+            gl.uniform_2_f32(Some(&self.u_board_size), width, height);
+        }
+    }
+    /// world area drawn, the part of the board in view
+    pub fn set_bounds(&self, gl: &glow::Context, min: &Position, max: &Position) {
+        unsafe {
+            gl.uniform_4_f32(Some(&self.u_bounds), min.x, min.y, max.x, max.y);
+        }
+    }
+    pub fn set_style(&self, gl: &glow::Context, style: GridStyle) {
+        unsafe {
+            gl.uniform_1_u32(Some(&self.u_style), style as u32);
+        }
+    }
+    /// cells to outline, in order the head, the pending turn & the targeted food
+    pub fn set_highlights(&self, gl: &glow::Context, cells: [Option<&Position>; 3]) {
+        let mut mask = 0;
+        for (i, (cell, loc)) in cells.iter().zip(&self.u_highlights).enumerate() {
+            if let Some(cell) = cell {
+                mask |= 1 << i;
+                unsafe {
+                    gl.uniform_2_f32(Some(loc), cell.x, cell.y);
+                }
+            }
+        }
+        unsafe {
+            gl.uniform_1_u32(Some(&self.u_highlight_mask), mask);
+        }
+    }
+}
+
+impl Shader for GridShader {
+    fn get_attribute(&self, key: &str) -> Option<u32> {
+        self.attributes.get(key).copied()
+    }
+    fn use_shader(&self, gl: &glow::Context) {
+        unsafe {
+            gl.use_program(Some(*self.program));
+        }
+    }
+}
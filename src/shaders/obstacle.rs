@@ -0,0 +1,38 @@
+use std::{collections::HashMap, rc::Rc};
+
+use glow::{HasContext, NativeProgram};
+
+use crate::{error::Error, gl_object::GlObject, shaders::{load_program, Shader}};
+
+/// one instance per obstacle, see `shader/obstacle.vs` for its attributes
+pub struct ObstacleShader {
+    program: GlObject<NativeProgram>,
+    attributes: HashMap<String, u32>,
+}
+
+impl ObstacleShader {
+    pub fn new(gl: &Rc<glow::Context>) -> Result<Self, Error> {
+        let attributes = [
+            ("aPos".to_string(), 0),
+            ("aCenter".to_string(), 1),
+            ("aHalfSize".to_string(), 2),
+        ];
+        let program = load_program(gl, "obstacle", "obstacle.vs", "obstacle.fs")?;
+
+        Ok(Self {
+            program,
+            attributes: HashMap::from(attributes),
+        })
+    }
+}
+
+impl Shader for ObstacleShader {
+    fn get_attribute(&self, key: &str) -> Option<u32> {
+        self.attributes.get(key).copied()
+    }
+    fn use_shader(&self, gl: &glow::Context) {
+        unsafe {
+            gl.use_program(Some(*self.program));
+        }
+    }
+}
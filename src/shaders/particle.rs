@@ -0,0 +1,38 @@
+use std::{collections::HashMap, rc::Rc};
+
+use glow::{HasContext, NativeProgram};
+
+use crate::{error::Error, gl_object::GlObject, shaders::{load_program, Shader}};
+
+pub struct ParticleShader {
+    program: GlObject<NativeProgram>,
+    attributes: HashMap<String, u32>,
+}
+
+impl ParticleShader {
+    pub fn new(gl: &Rc<glow::Context>) -> Result<Self, Error> {
+        let attributes = [
+            ("aPos".to_string(), 0),
+            ("aOffset".to_string(), 1),
+            ("aSize".to_string(), 2),
+            ("aColor".to_string(), 3),
+        ];
+        let program = load_program(gl, "particle", "particle.vs", "particle.fs")?;
+
+        Ok(Self {
+            program,
+            attributes: HashMap::from(attributes),
+        })
+    }
+}
+
+impl Shader for ParticleShader {
+    fn get_attribute(&self, key: &str) -> Option<u32> {
+        self.attributes.get(key).copied()
+    }
+    fn use_shader(&self, gl: &glow::Context) {
+        unsafe {
+            gl.use_program(Some(*self.program));
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use std::{collections::HashMap, rc::Rc};
+
+use glow::{HasContext, NativeProgram, NativeUniformLocation};
+
+use crate::{error::Error, gl_object::GlObject, shaders::{load_program, Shader}};
+
+/// full screen pass reading the previous pass from `uScene` <br/>
+/// a pass only declares the uniforms it needs, the others are ignored
+pub struct PostShader {
+    program: GlObject<NativeProgram>,
+    u_resolution: Option<NativeUniformLocation>,
+    u_direction: Option<NativeUniformLocation>,
+    u_offset: Option<NativeUniformLocation>,
+    u_time: Option<NativeUniformLocation>,
+    attributes: HashMap<String, u32>,
+}
+
+impl PostShader {
+    /// texture unit of the previous pass
+    pub const SCENE_UNIT: u32 = 0;
+    /// texture unit of the blurred highlights, only read by the bloom pass
+    pub const BLOOM_UNIT: u32 = 1;
+
+    /// `name` of the pass, loaded from `shader/post_<name>.fs`
+    pub fn new(gl: &Rc<glow::Context>, name: &str) -> Result<Self, Error> {
+        let attributes = [("aPos".to_string(), 0)];
+        let program = load_program(gl, &format!("post_{name}"), ".vs", &format!("post_{name}.fs"))?;
+
+        unsafe {
+            gl.use_program(Some(*program));
+            if let Some(loc) = gl.get_uniform_location(*program, "uScene") {
+                gl.uniform_1_i32(Some(&loc), Self::SCENE_UNIT as i32);
+            }
+            if let Some(loc) = gl.get_uniform_location(*program, "uBloom") {
+                gl.uniform_1_i32(Some(&loc), Self::BLOOM_UNIT as i32);
+            }
+        }
+        let (u_resolution, u_direction, u_offset, u_time) = unsafe {
+            (
+                gl.get_uniform_location(*program, "uResolution"),
+                gl.get_uniform_location(*program, "uDirection"),
+                gl.get_uniform_location(*program, "uOffset"),
+                gl.get_uniform_location(*program, "uTime"),
+            )
+        };
+
+        Ok(Self {
+            program,
+            u_resolution,
+            u_direction,
+            u_offset,
+            u_time,
+            attributes: HashMap::from(attributes),
+        })
+    }
+    /// size of the target being drawn into, in pixels
+    pub fn set_resolution(&self, gl: &glow::Context, width: f32, height: f32) {
+        unsafe {
+            gl.uniform_2_f32(self.u_resolution.as_ref(), width, height);
+        }
+    }
+    pub fn set_direction(&self, gl: &glow::Context, x: f32, y: f32) {
+        unsafe {
+            gl.uniform_2_f32(self.u_direction.as_ref(), x, y);
+        }
+    }
+    pub fn set_offset(&self, gl: &glow::Context, x: f32, y: f32) {
+        unsafe {
+            gl.uniform_2_f32(self.u_offset.as_ref(), x, y);
+        }
+    }
+    pub fn set_time(&self, gl: &glow::Context, x: f32) {
+        unsafe {
+            gl.uniform_1_f32(self.u_time.as_ref(), x);
+        }
+    }
+}
+
+impl Shader for PostShader {
+    fn get_attribute(&self, key: &str) -> Option<u32> {
+        self.attributes.get(key).copied()
+    }
+    fn use_shader(&self, gl: &glow::Context) {
+        unsafe {
+            gl.use_program(Some(*self.program));
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use std::{collections::HashMap, rc::Rc};
+
+use glow::{HasContext, NativeProgram};
+
+use crate::{error::Error, gl_object::GlObject, shaders::{load_program, Shader}};
+
+/// one instance per power up, see `shader/power_up.vs` for its attributes
+pub struct PowerUpShader {
+    program: GlObject<NativeProgram>,
+    attributes: HashMap<String, u32>,
+}
+
+impl PowerUpShader {
+    pub fn new(gl: &Rc<glow::Context>) -> Result<Self, Error> {
+        let attributes = [
+            ("aPos".to_string(), 0),
+            ("aCenter".to_string(), 1),
+            ("aRadius".to_string(), 2),
+            ("aTime".to_string(), 3),
+            ("aKind".to_string(), 4),
+        ];
+        let program = load_program(gl, "power_up", "power_up.vs", "power_up.fs")?;
+
+        Ok(Self {
+            program,
+            attributes: HashMap::from(attributes),
+        })
+    }
+}
+
+impl Shader for PowerUpShader {
+    fn get_attribute(&self, key: &str) -> Option<u32> {
+        self.attributes.get(key).copied()
+    }
+    fn use_shader(&self, gl: &glow::Context) {
+        unsafe {
+            gl.use_program(Some(*self.program));
+        }
+    }
+}
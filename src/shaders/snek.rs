@@ -1,8 +1,8 @@
-use crate::{objects::snek::DirKeypoint, shaders::{gen_program, Shader}};
+use crate::{app::board::Position, error::Error, gl_object::GlObject, objects::snek::DirKeypoint, shaders::{load_program, uniform, Shader}};
 use glow::{HasContext, NativeProgram, NativeUniformLocation};
-use std::{collections::HashMap, fs};
+use std::{collections::HashMap, rc::Rc};
 
-const MAX_KEYPOINTS: usize = 100;
+const MAX_KEYPOINTS: usize = 200;
 
 struct UKeypoint {
     from: NativeUniformLocation,
@@ -11,54 +11,44 @@ struct UKeypoint {
 }
 
 pub struct SnekShader {
-    program: NativeProgram,
+    program: GlObject<NativeProgram>,
     u_circ_radius: NativeUniformLocation,
     u_keypoints: [UKeypoint; MAX_KEYPOINTS],
     u_keypoint_len: NativeUniformLocation,
     u_length: NativeUniformLocation,
+    u_bounds: NativeUniformLocation,
     attributes: HashMap<String, u32>,
 }
 
 impl SnekShader {
-    pub fn new(gl: &glow::Context) -> Self {
+    pub fn new(gl: &Rc<glow::Context>) -> Result<Self, Error> {
         let attributes = [("aPos".to_string(), 0)];
-        let (vs, fs) = {
-            let vs = fs::read_to_string("./shader/.vs").expect("can't load vertex shader");
-            let fs = fs::read_to_string("./shader/snek.fs").expect("can't load fragment shader");
-            (vs, fs)
-        };
-        let program = gen_program(gl, &vs, &fs).unwrap();
+        let program = load_program(gl, "snek", "quad.vs", "snek.fs")?;
 
-        let (u_circ_radius, u_keypoint_len, u_length) = unsafe {
-            (
-                gl.get_uniform_location(program, "uCircRadius").unwrap(),
-                gl.get_uniform_location(program, "uKeypointLen").unwrap(),
-                gl.get_uniform_location(program, "uLength").unwrap(),
-            )
-        };
+        let (u_circ_radius, u_keypoint_len, u_length, u_bounds) = (
+            uniform(gl, *program, "snek", "uCircRadius")?,
+            uniform(gl, *program, "snek", "uKeypointLen")?,
+            uniform(gl, *program, "snek", "uLength")?,
+            uniform(gl, *program, "snek", "uBounds")?,
+        );
 
-        let u_keypoints: [UKeypoint; MAX_KEYPOINTS] = array_init::array_init(|i| unsafe {
-            UKeypoint {
-                at: gl
-                    .get_uniform_location(program, &format!("uKeypoints[{i}].at"))
-                    .unwrap(),
-                dst_head : gl
-                    .get_uniform_location(program, &format!("uKeypoints[{i}].dstHead"))
-                    .unwrap(),
-                from : gl
-                    .get_uniform_location(program, &format!("uKeypoints[{i}].from"))
-                    .unwrap(),
-            }
-        });
+        let u_keypoints: [UKeypoint; MAX_KEYPOINTS] = array_init::try_array_init(|i| -> Result<_, Error> {
+            Ok(UKeypoint {
+                at: uniform(gl, *program, "snek", &format!("uKeypoints[{i}].at"))?,
+                dst_head: uniform(gl, *program, "snek", &format!("uKeypoints[{i}].dstHead"))?,
+                from: uniform(gl, *program, "snek", &format!("uKeypoints[{i}].from"))?,
+            })
+        })?;
 
-        Self {
+        Ok(Self {
             program,
             u_circ_radius,
             u_keypoint_len,
             u_keypoints,
             u_length,
+            u_bounds,
             attributes: HashMap::from(attributes),
-        }
+        })
     }
 
     pub fn set_keypoints(&self, gl: &glow::Context, keypoints : &[DirKeypoint]) {
@@ -66,11 +56,9 @@ impl SnekShader {
         unsafe {
             gl.uniform_1_u32(Some(&self.u_keypoint_len), keypoints.len().try_into().unwrap());
         }
-        for i in 0..keypoints.len() {
-            let kp = &keypoints[i];
-            let kp_loc = &self.u_keypoints[i];
+        for (kp, kp_loc) in keypoints.iter().zip(&self.u_keypoints) {
             unsafe {
-                gl.uniform_1_u32(Some(&kp_loc.from), kp.from.clone() as u32);
+                gl.uniform_1_u32(Some(&kp_loc.from), kp.from as u32);
                 gl.uniform_2_f32(Some(&kp_loc.at), kp.at.x, kp.at.y);
                 gl.uniform_1_f32(Some(&kp_loc.dst_head), kp.dst_head);
             }
@@ -81,6 +69,12 @@ impl SnekShader {
             gl.uniform_1_f32(Some(&self.u_length), x);
         }
     }
+    /// world area the body fits in, only the fragments inside get shaded
+    pub fn set_bounds(&self, gl: &glow::Context, min: &Position, max: &Position) {
+        unsafe {
+            gl.uniform_4_f32(Some(&self.u_bounds), min.x, min.y, max.x, max.y);
+        }
+    }
     pub fn set_circle_radius(&self, gl: &glow::Context, x: f32) {
         unsafe {
             gl.uniform_1_f32(Some(&self.u_circ_radius), x);
@@ -94,7 +88,7 @@ impl Shader for SnekShader {
     }
     fn use_shader(&self, gl: &glow::Context) {
         unsafe {
-            gl.use_program(Some(self.program));
+            gl.use_program(Some(*self.program));
         }
     }
 }
@@ -0,0 +1,154 @@
+use glow::{HasContext, NativeProgram, NativeUniformLocation};
+use std::{collections::HashMap, rc::Rc};
+
+use crate::{error::Error, gl_object::GlObject, shaders::{load_program, uniform, Shader}};
+
+pub const MAX_CHARS: usize = 48;
+// width of a glyph including its spacing, in font pixels
+pub const GLYPH_ADVANCE: f32 = 4.;
+pub const GLYPH_HEIGHT: f32 = 5.;
+
+pub struct TextShader {
+    program: GlObject<NativeProgram>,
+    u_origin: NativeUniformLocation,
+    u_scale: NativeUniformLocation,
+    u_color: NativeUniformLocation,
+    u_glyphs: [NativeUniformLocation; MAX_CHARS],
+    u_len: NativeUniformLocation,
+    attributes: HashMap<String, u32>,
+}
+
+impl TextShader {
+    pub fn new(gl: &Rc<glow::Context>) -> Result<Self, Error> {
+        let attributes = [("aPos".to_string(), 0)];
+        let program = load_program(gl, "text", ".vs", "text.fs")?;
+
+        let (u_origin, u_scale, u_color, u_len) = (
+            uniform(gl, *program, "text", "uOrigin")?,
+            uniform(gl, *program, "text", "uScale")?,
+            uniform(gl, *program, "text", "uColor")?,
+            uniform(gl, *program, "text", "uLen")?,
+        );
+        let u_glyphs = array_init::try_array_init(|i| uniform(gl, *program, "text", &format!("uGlyphs[{i}]")))?;
+
+        Ok(Self {
+            program,
+            u_origin,
+            u_scale,
+            u_color,
+            u_glyphs,
+            u_len,
+            attributes: HashMap::from(attributes),
+        })
+    }
+
+    /// text longer than `MAX_CHARS` is cut off, lowercase is drawn as uppercase
+    pub fn set_text(&self, gl: &glow::Context, text: &str) {
+        let mut len = 0;
+        for (c, loc) in text.chars().zip(&self.u_glyphs) {
+            unsafe {
+                gl.uniform_1_u32(Some(loc), glyph(c));
+            }
+            len += 1;
+        }
+        unsafe {
+            gl.uniform_1_u32(Some(&self.u_len), len);
+        }
+    }
+    /// top left corner of the text in window pixels
+    pub fn set_origin(&self, gl: &glow::Context, x: f32, y: f32) {
+        unsafe {
+            gl.uniform_2_f32(Some(&self.u_origin), x, y);
+        }
+    }
+    /// size of a font pixel in window pixels
+    pub fn set_scale(&self, gl: &glow::Context, x: f32) {
+        unsafe {
+            gl.uniform_1_f32(Some(&self.u_scale), x);
+        }
+    }
+    pub fn set_color(&self, gl: &glow::Context, r: f32, g: f32, b: f32) {
+        unsafe {
+            gl.uniform_3_f32(Some(&self.u_color), r, g, b);
+        }
+    }
+}
+
+/// width of `text` in window pixels once drawn with `scale`
+pub fn text_width(text: &str, scale: f32) -> f32 {
+    text.chars().count().min(MAX_CHARS) as f32 * GLYPH_ADVANCE * scale - scale
+}
+
+impl Shader for TextShader {
+    fn get_attribute(&self, key: &str) -> Option<u32> {
+        self.attributes.get(key).copied()
+    }
+    fn use_shader(&self, gl: &glow::Context) {
+        unsafe {
+            gl.use_program(Some(*self.program));
+        }
+    }
+}
+
+// 3x5 bitmap, one 3 bit row per entry from top to bottom
+const fn rows(r: [u32; 5]) -> u32 {
+    (r[0] << 12) | (r[1] << 9) | (r[2] << 6) | (r[3] << 3) | r[4]
+}
+
+fn glyph(c: char) -> u32 {
+    match c.to_ascii_uppercase() {
+        '0' => rows([0b111, 0b101, 0b101, 0b101, 0b111]),
+        '1' => rows([0b010, 0b110, 0b010, 0b010, 0b111]),
+        '2' => rows([0b111, 0b001, 0b111, 0b100, 0b111]),
+        '3' => rows([0b111, 0b001, 0b111, 0b001, 0b111]),
+        '4' => rows([0b101, 0b101, 0b111, 0b001, 0b001]),
+        '5' => rows([0b111, 0b100, 0b111, 0b001, 0b111]),
+        '6' => rows([0b111, 0b100, 0b111, 0b101, 0b111]),
+        '7' => rows([0b111, 0b001, 0b001, 0b001, 0b001]),
+        '8' => rows([0b111, 0b101, 0b111, 0b101, 0b111]),
+        '9' => rows([0b111, 0b101, 0b111, 0b001, 0b111]),
+        'A' => rows([0b010, 0b101, 0b111, 0b101, 0b101]),
+        'B' => rows([0b110, 0b101, 0b110, 0b101, 0b110]),
+        'C' => rows([0b011, 0b100, 0b100, 0b100, 0b011]),
+        'D' => rows([0b110, 0b101, 0b101, 0b101, 0b110]),
+        'E' => rows([0b111, 0b100, 0b110, 0b100, 0b111]),
+        'F' => rows([0b111, 0b100, 0b110, 0b100, 0b100]),
+        'G' => rows([0b011, 0b100, 0b101, 0b101, 0b011]),
+        'H' => rows([0b101, 0b101, 0b111, 0b101, 0b101]),
+        'I' => rows([0b111, 0b010, 0b010, 0b010, 0b111]),
+        'J' => rows([0b001, 0b001, 0b001, 0b101, 0b010]),
+        'K' => rows([0b101, 0b101, 0b110, 0b101, 0b101]),
+        'L' => rows([0b100, 0b100, 0b100, 0b100, 0b111]),
+        'M' => rows([0b101, 0b111, 0b111, 0b101, 0b101]),
+        'N' => rows([0b110, 0b101, 0b101, 0b101, 0b101]),
+        'O' => rows([0b010, 0b101, 0b101, 0b101, 0b010]),
+        'P' => rows([0b110, 0b101, 0b110, 0b100, 0b100]),
+        'Q' => rows([0b010, 0b101, 0b101, 0b110, 0b011]),
+        'R' => rows([0b110, 0b101, 0b110, 0b101, 0b101]),
+        'S' => rows([0b011, 0b100, 0b010, 0b001, 0b110]),
+        'T' => rows([0b111, 0b010, 0b010, 0b010, 0b010]),
+        'U' => rows([0b101, 0b101, 0b101, 0b101, 0b111]),
+        'V' => rows([0b101, 0b101, 0b101, 0b101, 0b010]),
+        'W' => rows([0b101, 0b101, 0b111, 0b111, 0b101]),
+        'X' => rows([0b101, 0b101, 0b010, 0b101, 0b101]),
+        'Y' => rows([0b101, 0b101, 0b010, 0b010, 0b010]),
+        'Z' => rows([0b111, 0b001, 0b010, 0b100, 0b111]),
+        ' ' => 0,
+        ':' => rows([0b000, 0b010, 0b000, 0b010, 0b000]),
+        '.' => rows([0b000, 0b000, 0b000, 0b000, 0b010]),
+        ',' => rows([0b000, 0b000, 0b000, 0b010, 0b100]),
+        '-' => rows([0b000, 0b000, 0b111, 0b000, 0b000]),
+        '+' => rows([0b000, 0b010, 0b111, 0b010, 0b000]),
+        '=' => rows([0b000, 0b111, 0b000, 0b111, 0b000]),
+        '/' => rows([0b001, 0b001, 0b010, 0b100, 0b100]),
+        '%' => rows([0b101, 0b001, 0b010, 0b100, 0b101]),
+        '(' => rows([0b010, 0b100, 0b100, 0b100, 0b010]),
+        ')' => rows([0b010, 0b001, 0b001, 0b001, 0b010]),
+        '<' => rows([0b001, 0b010, 0b100, 0b010, 0b001]),
+        '>' => rows([0b100, 0b010, 0b001, 0b010, 0b100]),
+        '!' => rows([0b010, 0b010, 0b010, 0b000, 0b010]),
+        '\'' => rows([0b010, 0b010, 0b000, 0b000, 0b000]),
+        '_' => rows([0b000, 0b000, 0b000, 0b000, 0b111]),
+        _ => rows([0b111, 0b001, 0b010, 0b000, 0b010]),
+    }
+}